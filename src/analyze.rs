@@ -1,132 +1,155 @@
 use crate::DumpLammpsReader;
-use anyhow::Result;
+use crate::dump_reader::DumpSelection;
+use crate::error::LammpsError;
 use polars::prelude::*;
 use std::collections::BTreeMap;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
-pub struct AnalyzeLammps {
-    file_name: PathBuf,
-}
+pub struct AnalyzeLammps;
 
 impl AnalyzeLammps {
-    pub fn unwrap(mut trajectories: Vec<DataFrame>, box_bounds: &DataFrame) -> Vec<DataFrame> {
-        for (i, df) in trajectories.iter_mut().enumerate() {
-            let xlo = box_bounds
-                .column("xlo")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(i)
-                .unwrap();
-            let xhi = box_bounds
-                .column("xhi")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(i)
-                .unwrap();
-            let ylo = box_bounds
-                .column("ylo")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(i)
-                .unwrap();
-            let yhi = box_bounds
-                .column("yhi")
-                .unwrap()
-                .f64()
-                .unwrap()
-                .get(i)
-                .unwrap();
-            let zlo = box_bounds
-                .column("zlo")
-                .unwrap()
+    pub fn unwrap(
+        mut trajectories: Vec<DataFrame>,
+        box_bounds: &DataFrame,
+        dump_file_name: &Path,
+    ) -> Result<Vec<DataFrame>, LammpsError> {
+        let polars_error = |source: PolarsError| LammpsError::Polars {
+            path: dump_file_name.to_path_buf(),
+            source,
+        };
+        let missing_values = |name: &str| LammpsError::InvalidData {
+            path: dump_file_name.to_path_buf(),
+            line: None,
+            message: format!(
+                "Column '{}' of the dump file '{}' is missing or has null values",
+                name,
+                dump_file_name.display()
+            ),
+        };
+        let bound = |name: &str, i: usize| -> Result<f64, LammpsError> {
+            box_bounds
+                .column(name)
+                .map_err(polars_error)?
                 .f64()
-                .unwrap()
+                .map_err(polars_error)?
                 .get(i)
-                .unwrap();
-            let zhi = box_bounds
-                .column("zhi")
-                .unwrap()
+                .ok_or_else(|| missing_values(name))
+        };
+        let coordinates = |df: &DataFrame, name: &str| -> Result<Vec<f64>, LammpsError> {
+            df.column(name)
+                .map_err(|_| missing_values(name))?
+                .cast(&DataType::Float64)
+                .map_err(polars_error)?
                 .f64()
-                .unwrap()
-                .get(i)
-                .unwrap();
-
-            let lx = xhi - xlo;
-            let ly = yhi - ylo;
-            let lz = zhi - zlo;
+                .map_err(polars_error)?
+                .into_iter()
+                .map(|value| value.ok_or_else(|| missing_values(name)))
+                .collect()
+        };
 
-            let x_vals = df.column("x").unwrap().f64().unwrap();
-            let y_vals = df.column("y").unwrap().f64().unwrap();
-            let z_vals = df.column("z").unwrap().f64().unwrap();
+        for (i, df) in trajectories.iter_mut().enumerate() {
+            let lx = bound("xhi", i)? - bound("xlo", i)?;
+            let ly = bound("yhi", i)? - bound("ylo", i)?;
+            let lz = bound("zhi", i)? - bound("zlo", i)?;
+
+            // Only periodic axes are unwrapped, dumps without boundary styles are assumed periodic
+            let periodic = |axis: &str| -> bool {
+                box_bounds
+                    .column(&format!("{}_boundary", axis))
+                    .ok()
+                    .and_then(|column| column.str().ok()?.get(i))
+                    .is_none_or(|style| style.starts_with('p'))
+            };
+            let (x_periodic, y_periodic, z_periodic) =
+                (periodic("x"), periodic("y"), periodic("z"));
+
+            let x_vals: Vec<f64> = coordinates(df, "x")?;
+            let y_vals: Vec<f64> = coordinates(df, "y")?;
+            let z_vals: Vec<f64> = coordinates(df, "z")?;
+            if x_vals.is_empty() {
+                continue;
+            }
 
-            let mut x_unwrapped = vec![x_vals.get(0).unwrap()];
-            let mut y_unwrapped = vec![y_vals.get(0).unwrap()];
-            let mut z_unwrapped = vec![z_vals.get(0).unwrap()];
+            let mut x_unwrapped = vec![x_vals[0]];
+            let mut y_unwrapped = vec![y_vals[0]];
+            let mut z_unwrapped = vec![z_vals[0]];
 
             for j in 1..x_vals.len() {
-                let mut xj = x_vals.get(j).unwrap();
+                let mut xj = x_vals[j];
                 let dx = xj - x_unwrapped[j - 1];
-                if dx > lx / 2.0 {
+                if x_periodic && dx > lx / 2.0 {
                     xj -= lx;
-                } else if dx < -lx / 2.0 {
+                } else if x_periodic && dx < -lx / 2.0 {
                     xj += lx;
                 }
                 x_unwrapped.push(xj);
 
-                let mut yj = y_vals.get(j).unwrap();
+                let mut yj = y_vals[j];
                 let dy = yj - y_unwrapped[j - 1];
-                if dy > ly / 2.0 {
+                if y_periodic && dy > ly / 2.0 {
                     yj -= ly;
-                } else if dy < -ly / 2.0 {
+                } else if y_periodic && dy < -ly / 2.0 {
                     yj += ly;
                 }
                 y_unwrapped.push(yj);
 
-                let mut zj = z_vals.get(j).unwrap();
+                let mut zj = z_vals[j];
                 let dz = zj - z_unwrapped[j - 1];
-                if dz > lz / 2.0 {
+                if z_periodic && dz > lz / 2.0 {
                     zj -= lz;
-                } else if dz < -lz / 2.0 {
+                } else if z_periodic && dz < -lz / 2.0 {
                     zj += lz;
                 }
                 z_unwrapped.push(zj);
             }
 
-            df.replace("x".into(), Series::new("x".into(), x_unwrapped))
-                .unwrap();
-            df.replace("y".into(), Series::new("y".into(), y_unwrapped))
-                .unwrap();
-            df.replace("z".into(), Series::new("z".into(), z_unwrapped))
-                .unwrap();
+            df.replace("x", Series::new("x".into(), x_unwrapped))
+                .map_err(polars_error)?;
+            df.replace("y", Series::new("y".into(), y_unwrapped))
+                .map_err(polars_error)?;
+            df.replace("z", Series::new("z".into(), z_unwrapped))
+                .map_err(polars_error)?;
         }
 
-        trajectories
+        Ok(trajectories)
     }
     pub fn mean_square_displacement(
         file_name: PathBuf,
         unwrap_trajectory: bool,
-    ) -> Result<BTreeMap<u64, f64>, Box<dyn std::error::Error>> {
+    ) -> Result<BTreeMap<u64, f64>, LammpsError> {
         let mut msd_map: BTreeMap<u64, f64> = BTreeMap::new();
         let mut system = DumpLammpsReader {
             dump_file_name: file_name,
+            selection: DumpSelection::default(),
             timesteps: Vec::new(),
             trajectories: Vec::new(),
             box_state: DataFrame::empty(),
         };
         system.parse_lammps_dump()?;
         if unwrap_trajectory {
-            system.trajectories = Self::unwrap(system.trajectories, &system.box_state);
+            system.trajectories = Self::unwrap(
+                system.trajectories,
+                &system.box_state,
+                &system.dump_file_name,
+            )?;
         }
 
         let dump_data: BTreeMap<u64, DataFrame> = system.get_dump_map()?;
 
         // Assume first timestep is initial positions
-        let Some(first_df) = system.trajectories.iter().next() else {
-        return Err("Empty dump data".into());
-    };
+        let Some(first_df) = system.trajectories.first() else {
+            return Err(LammpsError::UnsupportedFormat {
+                path: system.dump_file_name.clone(),
+                message: format!(
+                    "No frames found in the dump file '{}'",
+                    system.dump_file_name.display()
+                ),
+            });
+        };
+        let polars_error = |source: PolarsError| LammpsError::Polars {
+            path: system.dump_file_name.clone(),
+            source,
+        };
 
         let initial_positions = first_df
             .clone()
@@ -137,7 +160,8 @@ impl AnalyzeLammps {
                 col("y").alias("y0"),
                 col("z").alias("z0"),
             ])
-            .collect()?;
+            .collect()
+            .map_err(polars_error)?;
 
         for (timestep, df) in dump_data {
             let msd_df = df
@@ -153,9 +177,16 @@ impl AnalyzeLammps {
                     + (col("z") - col("z0")).pow(2))
                 .alias("r_squared")])
                 .select([col("r_squared").mean().alias("msd")])
-                .collect()?;
+                .collect()
+                .map_err(polars_error)?;
 
-            if let Some(msd_value) = msd_df.column("msd")?.f64()?.get(0) {
+            if let Some(msd_value) = msd_df
+                .column("msd")
+                .map_err(polars_error)?
+                .f64()
+                .map_err(polars_error)?
+                .get(0)
+            {
                 msd_map.insert(timestep, msd_value);
             }
         }
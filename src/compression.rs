@@ -0,0 +1,48 @@
+use flate2::read::MultiGzDecoder;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use xz2::read::XzDecoder;
+
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+const XZ_MAGIC: &[u8] = &[0xfd, b'7', b'z', b'X', b'Z', 0x00];
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression of a file, detected from its magic bytes
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Xz,
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression from the first bytes of a file
+    pub fn detect(magic: &[u8]) -> Compression {
+        if magic.starts_with(GZIP_MAGIC) {
+            Compression::Gzip
+        } else if magic.starts_with(XZ_MAGIC) {
+            Compression::Xz
+        } else if magic.starts_with(ZSTD_MAGIC) {
+            Compression::Zstd
+        } else {
+            Compression::None
+        }
+    }
+}
+
+/** Returns a reader which decompresses the file while it is read.
+
+The compression is detected from the magic bytes, not the file extension, and
+files which are not compressed are read as they are. Concatenated gzip, xz and
+zstd streams are read as a single file. */
+pub fn decompressed_reader(file: File) -> std::io::Result<Box<dyn BufRead + Send>> {
+    let mut file_reader: BufReader<File> = BufReader::new(file);
+    let compression: Compression = Compression::detect(file_reader.fill_buf()?);
+    Ok(match compression {
+        Compression::None => Box::new(file_reader),
+        Compression::Gzip => Box::new(BufReader::new(MultiGzDecoder::new(file_reader))),
+        Compression::Xz => Box::new(BufReader::new(XzDecoder::new_multi_decoder(file_reader))),
+        Compression::Zstd => Box::new(BufReader::new(zstd::Decoder::with_buffer(file_reader)?)),
+    })
+}
@@ -1,25 +1,157 @@
+use crate::compression::{Compression, decompressed_reader};
+use crate::error::LammpsError;
 use polars::prelude::*;
 use rayon::prelude::*;
-use std::collections::BTreeMap;
+use std::collections::{BTreeMap, BTreeSet};
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+const ITEM_FLAG: &str = "ITEM:";
+/// First line of the sidecar file of a dump frame index
+const SIDECAR_HEADER: &str = "# log_lammps_reader dump frame index v2";
+/// Extension added to the dump file name for the sidecar file of its frame index
+const SIDECAR_EXTENSION: &str = "idx";
+/// `ITEM: BOX BOUNDS xy xz yz pp pp pp` is printed for triclinic boxes
+const TILT_FLAG: &str = "xy xz yz";
+
+/** Simulation box of a dump frame.
+
+For a triclinic box, LAMMPS prints the bounding box of the tilted cell along
+with the tilt factors. `lo` and `hi` are the bounds of the cell itself, which
+are the same as the bounding box for an orthogonal box. */
+#[derive(Clone, Debug, PartialEq)]
+pub struct DumpBox {
+    /// `xlo ylo zlo`
+    pub lo: [f64; 3],
+    /// `xhi yhi zhi`
+    pub hi: [f64; 3],
+    /// `xy xz yz` tilt factors, zero for an orthogonal box
+    pub tilt: [f64; 3],
+    pub triclinic: bool,
+    /// Boundary styles of the x, y and z axes, e.g. `pp`, `ff` or `sm`
+    pub boundary: Option<[String; 3]>,
+}
+
+impl DumpBox {
+    /// Creates the box from the `lo hi` (and `tilt` if triclinic) values of the x, y and z lines
+    fn from_bounds(
+        bounds: [[f64; 3]; 3],
+        triclinic: bool,
+        boundary: Option<[String; 3]>,
+    ) -> DumpBox {
+        let [
+            [xlo_bound, xhi_bound, xy],
+            [ylo_bound, yhi_bound, xz],
+            [zlo, zhi, yz],
+        ] = bounds;
+        if !triclinic {
+            return DumpBox {
+                lo: [xlo_bound, ylo_bound, zlo],
+                hi: [xhi_bound, yhi_bound, zhi],
+                tilt: [0.0; 3],
+                triclinic,
+                boundary,
+            };
+        }
+        let x_shifts: [f64; 4] = [0.0, xy, xz, xy + xz];
+        let x_min: f64 = x_shifts.iter().copied().fold(f64::INFINITY, f64::min);
+        let x_max: f64 = x_shifts.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        DumpBox {
+            lo: [xlo_bound - x_min, ylo_bound - yz.min(0.0), zlo],
+            hi: [xhi_bound - x_max, yhi_bound - yz.max(0.0), zhi],
+            tilt: [xy, xz, yz],
+            triclinic,
+            boundary,
+        }
+    }
+
+    /// Edge lengths `lx ly lz` of the cell
+    pub fn lengths(&self) -> [f64; 3] {
+        [
+            self.hi[0] - self.lo[0],
+            self.hi[1] - self.lo[1],
+            self.hi[2] - self.lo[2],
+        ]
+    }
+
+    /** Returns the cell matrix, where the rows are the edge vectors of the cell:
+    `a = (lx, 0, 0)`, `b = (xy, ly, 0)` and `c = (xz, yz, lz)`. */
+    pub fn cell_matrix(&self) -> [[f64; 3]; 3] {
+        let [lx, ly, lz] = self.lengths();
+        let [xy, xz, yz] = self.tilt;
+        [[lx, 0.0, 0.0], [xy, ly, 0.0], [xz, yz, lz]]
+    }
+}
+
+/** Frames of a dump file to parse, every frame by default.
+
+A frame is kept if it matches every option which is set. Frames which are not
+kept are stepped over without parsing their atoms. */
+#[derive(Clone, Debug, Default)]
+pub struct DumpSelection {
+    /// Index of the first frame, starting at 0
+    pub start: Option<usize>,
+    /// Index of the frame where reading stops, not included
+    pub stop: Option<usize>,
+    /// Keeps every `step`-th frame from `start`
+    pub step: Option<usize>,
+    /// Smallest timestep to keep
+    pub min_timestep: Option<u64>,
+    /// Largest timestep to keep
+    pub max_timestep: Option<u64>,
+    /// Timesteps to keep
+    pub timesteps: Option<BTreeSet<u64>>,
+}
+
+impl DumpSelection {
+    /// Checks if the frame at `frame_id` of the dump file is kept
+    pub fn contains(&self, frame_id: usize, timestep: u64) -> bool {
+        let start: usize = self.start.unwrap_or(0);
+        frame_id >= start
+            && !self.is_past_end(frame_id)
+            && (frame_id - start).is_multiple_of(self.step.unwrap_or(1).max(1))
+            && self.min_timestep.is_none_or(|min| timestep >= min)
+            && self.max_timestep.is_none_or(|max| timestep <= max)
+            && self
+                .timesteps
+                .as_ref()
+                .is_none_or(|timesteps| timesteps.contains(&timestep))
+    }
+
+    /// Checks if no frame from `frame_id` onwards is kept
+    pub fn is_past_end(&self, frame_id: usize) -> bool {
+        self.stop.is_some_and(|stop| frame_id >= stop)
+    }
+}
 
 /** This Rust code parses LAMMPS dump files */
 pub struct DumpLammpsReader {
     pub dump_file_name: PathBuf,
+    pub selection: DumpSelection,
     pub timesteps: Vec<u64>,
     pub trajectories: Vec<DataFrame>,
     pub box_state: DataFrame,
 }
 
 impl DumpLammpsReader {
-    // API to parse LAMMPS dump file
+    /** API to parse the selected frames of a LAMMPS dump file.
+
+    ### Parameters:
+    dump_file_name: File path for the LAMMPS dump file
+    selection: Frame indices and timesteps of the frames to parse, every frame with
+        `DumpSelection::default()`. The atom lines of the other frames are not parsed,
+        and reading stops at `selection.stop`
+
+    Returns a map of the timesteps and the atoms of the selected frames */
     pub fn parse(
         dump_file_name: PathBuf,
-    ) -> Result<BTreeMap<u64, DataFrame>, Box<dyn std::error::Error>> {
+        selection: DumpSelection,
+    ) -> Result<BTreeMap<u64, DataFrame>, LammpsError> {
         let mut system = DumpLammpsReader {
             dump_file_name,
+            selection,
             timesteps: Vec::new(),
             trajectories: Vec::new(),
             box_state: DataFrame::empty(),
@@ -28,9 +160,10 @@ impl DumpLammpsReader {
         system.get_dump_map()
     }
 
-    pub fn parse_state(dump_file_name: PathBuf) -> Result<DataFrame, Box<dyn std::error::Error>> {
+    pub fn parse_state(dump_file_name: PathBuf) -> Result<DataFrame, LammpsError> {
         let mut system = DumpLammpsReader {
             dump_file_name,
+            selection: DumpSelection::default(),
             timesteps: Vec::new(),
             trajectories: Vec::new(),
             box_state: DataFrame::empty(),
@@ -39,170 +172,1018 @@ impl DumpLammpsReader {
         Ok(system.box_state)
     }
 
+    /** Reads the simulation box of every frame without parsing the atoms.
+
+    ### Parameters:
+    dump_file_name: File path for the LAMMPS dump file
+
+    Returns a map of the timesteps and the box of each frame, whose `cell_matrix`
+    gives the edge vectors of a triclinic cell. */
+    pub fn parse_boxes(dump_file_name: PathBuf) -> Result<BTreeMap<u64, DumpBox>, LammpsError> {
+        let system = DumpLammpsReader {
+            dump_file_name,
+            selection: DumpSelection::default(),
+            timesteps: Vec::new(),
+            trajectories: Vec::new(),
+            box_state: DataFrame::empty(),
+        };
+        Ok(system
+            .read_dump_frames()?
+            .into_iter()
+            .map(|frame| (frame.timestep, frame.dump_box))
+            .collect())
+    }
+
     // Parse LAMMPS dump file
-    pub fn parse_lammps_dump(&mut self) -> Result<(), Box<dyn std::error::Error>> {
-        let mut timesteps: Vec<String> = Vec::new();
-        let mut atoms: Vec<String> = Vec::new();
-        let mut single_dump_data: Vec<String> = Vec::new();
-        let mut dump_data: Vec<Vec<String>> = Vec::new();
-        let mut header: String = String::new();
-        let mut start_parse_data: bool = false;
-        let mut parse_in_progress: bool = false;
-        let mut x_bounds: Vec<String> = Vec::new();
-        let mut y_bounds: Vec<String> = Vec::new();
-        let mut z_bounds: Vec<String> = Vec::new();
-        let dump_file: File = File::open(&self.dump_file_name).map_err(|_| {
-            format!(
-                "Dump file at '{}' not found...\nCheck 'dump_file_name' parameter",
-                &self.dump_file_name.display()
-            )
+    pub fn parse_lammps_dump(&mut self) -> Result<(), LammpsError> {
+        let frames: Vec<RawDumpFrame> = self.read_dump_frames()?;
+
+        // INFO: Begin parsing data into types
+        self.trajectories = frames
+            .par_iter()
+            .map(|frame| frame.to_df(&self.dump_file_name))
+            .collect::<Result<Vec<DataFrame>, LammpsError>>()?;
+        self.timesteps = frames.iter().map(|frame| frame.timestep).collect();
+
+        //INFO: Parse system information in a dataframe
+        let box_values = |value: fn(&DumpBox) -> f64| -> Vec<f64> {
+            frames.iter().map(|frame| value(&frame.dump_box)).collect()
+        };
+        let boundary = |axis: usize| -> Vec<Option<&str>> {
+            frames
+                .iter()
+                .map(|frame| {
+                    let boundary = frame.dump_box.boundary.as_ref()?;
+                    Some(boundary[axis].as_str())
+                })
+                .collect()
+        };
+        self.box_state = df![
+            "timestep" => &self.timesteps,
+            "time" => frames.iter().map(|frame| frame.time).collect::<Vec<Option<f64>>>(),
+            "atoms" => frames.iter().map(|frame| frame.atoms).collect::<Vec<u64>>(),
+            "xlo" => box_values(|dump_box| dump_box.lo[0]),
+            "xhi" => box_values(|dump_box| dump_box.hi[0]),
+            "ylo" => box_values(|dump_box| dump_box.lo[1]),
+            "yhi" => box_values(|dump_box| dump_box.hi[1]),
+            "zlo" => box_values(|dump_box| dump_box.lo[2]),
+            "zhi" => box_values(|dump_box| dump_box.hi[2]),
+            "xy" => box_values(|dump_box| dump_box.tilt[0]),
+            "xz" => box_values(|dump_box| dump_box.tilt[1]),
+            "yz" => box_values(|dump_box| dump_box.tilt[2]),
+            "x_boundary" => boundary(0),
+            "y_boundary" => boundary(1),
+            "z_boundary" => boundary(2),
+            "units" => frames
+                .iter()
+                .map(|frame| frame.units.as_deref())
+                .collect::<Vec<Option<&str>>>(),
+        ]
+        .map_err(|source| LammpsError::Polars {
+            path: self.dump_file_name.clone(),
+            source,
         })?;
-        let dump_reader: BufReader<File> = BufReader::new(dump_file);
-        let mut lines = dump_reader.lines().peekable();
-        while let Some(line_result) = lines.next() {
-            let line: String = line_result?;
-            if line.starts_with("ITEM: TIMESTEP") {
-                timesteps.push(lines.next().unwrap().unwrap());
-                start_parse_data = false;
-            }
-            if line.starts_with("ITEM: NUMBER OF ATOMS") {
-                atoms.push(lines.next().unwrap().unwrap());
-                start_parse_data = false;
-            }
-            if line.starts_with("ITEM: BOX BOUNDS") {
-                start_parse_data = false;
-                x_bounds.push(lines.next().unwrap().unwrap());
-                y_bounds.push(lines.next().unwrap().unwrap());
-                z_bounds.push(lines.next().unwrap().unwrap());
-                continue;
-            }
-            if line.starts_with("ITEM: ATOMS") {
-                header = line;
-                start_parse_data = true;
-                if parse_in_progress {
-                    dump_data.push(single_dump_data.clone());
-                    single_dump_data.clear();
-                }
-                continue;
-            }
-            // start parsing ATOMS
-            if start_parse_data {
-                parse_in_progress = true;
-                single_dump_data.push(line);
+
+        Ok(())
+    }
+
+    /// Reads the sections of the selected frames in the dump file, without parsing the atoms
+    fn read_dump_frames(&self) -> Result<Vec<RawDumpFrame>, LammpsError> {
+        let mut frame_reader: DumpFrameReader = DumpFrameReader::open(&self.dump_file_name)?;
+        let mut frames: Vec<RawDumpFrame> = Vec::new();
+        let mut frame_id: usize = 0;
+        while !self.selection.is_past_end(frame_id)
+            && let Some(mut frame) = frame_reader.next_header()?
+        {
+            if self.selection.contains(frame_id, frame.timestep) {
+                frame_reader.read_atoms(&mut frame)?;
+                frames.push(frame);
+            } else {
+                frame_reader.skip_atoms()?;
             }
+            frame_id += 1;
         }
+        Ok(frames)
+    }
+
+    /** Finds the byte offset of every frame, so that frames can be read without
+    reading the frames before them. Only uncompressed dump files can be indexed.
+
+    ### Parameters:
+    dump_file_name: File path for the LAMMPS dump file
+    sidecar: Load the index from `<dump_file_name>.idx`, or save it there when the
+        file does not exist or was made for a different size or modification time
+        of the dump file. The index is still returned if it cannot be saved
+
+    The dump file is read once with a buffered reader to find the frames.
 
-        if !single_dump_data.is_empty() {
-            dump_data.push(single_dump_data);
+    Returns a DumpFrameIndex to read frames by index or timestep */
+    pub fn index(dump_file_name: PathBuf, sidecar: bool) -> Result<DumpFrameIndex, LammpsError> {
+        let io_error = |source: std::io::Error| LammpsError::Io {
+            path: dump_file_name.clone(),
+            source,
+        };
+        let dump_file: File = open_dump_file(&dump_file_name)?;
+        let metadata: std::fs::Metadata = dump_file.metadata().map_err(io_error)?;
+        let dump_file_size: u64 = metadata.len();
+        // Without a modification time, a rewritten dump file cannot be told apart
+        let stamp: Option<String> = metadata
+            .modified()
+            .ok()
+            .and_then(|modified| modified.duration_since(std::time::UNIX_EPOCH).ok())
+            .map(|modified| format!("size {} modified {}", dump_file_size, modified.as_nanos()));
+        let stamp: Option<&str> = stamp.as_deref().filter(|_| sidecar);
+        let mut sidecar_file_name = dump_file_name.clone().into_os_string();
+        sidecar_file_name.push(".");
+        sidecar_file_name.push(SIDECAR_EXTENSION);
+        let sidecar_file_name: PathBuf = sidecar_file_name.into();
+        if let Some(stamp) = stamp
+            && let Some(frames) = DumpFrameIndex::read_sidecar(&sidecar_file_name, stamp)
+        {
+            return Ok(DumpFrameIndex {
+                dump_file_name,
+                frames,
+            });
         }
 
-        // INFO: Begin parsing data into types
-        self.timesteps = timesteps
-            .iter()
-            .map(|x| x.split_whitespace().last().unwrap().parse::<u64>().unwrap())
-            .collect();
-        let atoms: Vec<u64> = atoms
+        if dump_file_size == 0 {
+            return Err(LammpsError::UnsupportedFormat {
+                path: dump_file_name.clone(),
+                message: format!(
+                    "No 'ITEM:' sections found in the dump file '{}'",
+                    dump_file_name.display()
+                ),
+            });
+        }
+        let mut dump_reader: BufReader<File> = BufReader::new(dump_file);
+        let dump_start: &[u8] = dump_reader.fill_buf().map_err(io_error)?;
+        if Compression::detect(dump_start) != Compression::None {
+            return Err(LammpsError::UnsupportedFormat {
+                path: dump_file_name.clone(),
+                message: format!(
+                    "Dump file '{}' is compressed, only uncompressed dump files can be indexed",
+                    dump_file_name.display()
+                ),
+            });
+        }
+        let starts_with_item: bool = dump_start.starts_with(ITEM_FLAG.as_bytes());
+        let index = DumpFrameIndex {
+            frames: DumpFrameIndex::scan(&dump_file_name, dump_reader)?,
+            dump_file_name,
+        };
+        if index.is_empty() && !starts_with_item {
+            return Err(LammpsError::UnsupportedFormat {
+                path: index.dump_file_name.clone(),
+                message: format!(
+                    "Dump file at '{}' does not start with an 'ITEM:' line",
+                    index.dump_file_name.display()
+                ),
+            });
+        }
+        if let Some(stamp) = stamp {
+            // The sidecar file is only a cache, e.g. the directory may be read-only
+            let _ = index.write_sidecar(&sidecar_file_name, stamp);
+        }
+        Ok(index)
+    }
+
+    /** Creates an iterator which parses the frames of the dump file one at a time.
+
+    Only the lines of the current frame are stored, so the memory does not grow
+    with the size of the dump file.
+
+    ### Parameters:
+    dump_file_name: File path for the LAMMPS dump file
+
+    Returns a DumpFrameIter which yields the timestep, box and atoms of each frame. */
+    pub fn iter_frames(dump_file_name: PathBuf) -> Result<DumpFrameIter, LammpsError> {
+        Ok(DumpFrameIter {
+            frame_reader: DumpFrameReader::open(&dump_file_name)?,
+            finished: false,
+        })
+    }
+
+    pub fn get_dump_map(&self) -> Result<BTreeMap<u64, DataFrame>, LammpsError> {
+        let data_map: BTreeMap<u64, DataFrame> = self
+            .timesteps
             .iter()
-            .map(|x| x.split_whitespace().last().unwrap().parse::<u64>().unwrap())
+            .cloned()
+            .zip(self.trajectories.iter().cloned())
             .collect();
-        let header = header
-            .strip_prefix("ITEM: ATOMS")
-            .expect("Failed to parse header...")
-            .split_whitespace()
-            .collect::<Vec<&str>>();
 
-        self.trajectories = dump_data
-            .par_iter()
-            .map(|single_dump_data| {
-                // Parse data
-                let parsed_data: Vec<Vec<String>> = single_dump_data
-                    .iter()
-                    .map(|line| line.split_whitespace().map(String::from).collect())
-                    .collect();
-
-                // Transpose data into columns
-                let mut columns: Vec<Column> = Vec::new();
-                for (col_idx, col_name) in header.iter().enumerate() {
-                    let col_data: Vec<&str> = parsed_data
-                        .iter()
-                        .map(|row| row[col_idx].as_str())
-                        .collect();
-
-                    let series = if col_data.iter().all(|v| v.parse::<i64>().is_ok()) {
-                        let col_values: Vec<i64> =
-                            col_data.iter().map(|v| v.parse().unwrap()).collect();
-                        Column::new(col_name.to_string().into(), col_values)
-                    } else if col_data.iter().all(|v| v.parse::<f64>().is_ok()) {
-                        let col_values: Vec<f64> =
-                            col_data.iter().map(|v| v.parse().unwrap()).collect();
-                        Column::new(col_name.to_string().into(), col_values)
-                    } else {
-                        let col_values: Vec<String> =
-                            col_data.iter().map(|&v| v.to_string()).collect();
-                        Column::new(col_name.to_string().into(), col_values)
-                    };
-
-                    columns.push(series);
-                }
-                DataFrame::new(columns).expect("Failed to create DataFrame")
-            })
-            .collect();
+        Ok(data_map)
+    }
+}
 
-        //INFO: Parse system information in a dataframe
-        let (xlo, xhi): (Vec<f64>, Vec<f64>) = x_bounds
-            .iter()
-            .map(|s| {
-                let mut split = s.split_whitespace();
-                (
-                    split.next().unwrap().parse::<f64>().unwrap(),
-                    split.next().unwrap().parse::<f64>().unwrap(),
-                )
-            })
-            .unzip();
+/// Sections of a frame in the dump file, with the atoms as they were read
+struct RawDumpFrame {
+    timestep: u64,
+    atoms: u64,
+    dump_box: DumpBox,
+    /// Simulation time of `dump_modify time yes`
+    time: Option<f64>,
+    /// Units of `dump_modify units yes`
+    units: Option<String>,
+    columns: Vec<String>,
+    /// Line number of `ITEM: ATOMS`
+    header_line: usize,
+    /// Line number and text of every atom
+    rows: Vec<(usize, String)>,
+}
 
-        let (ylo, yhi): (Vec<f64>, Vec<f64>) = y_bounds
+impl RawDumpFrame {
+    /// Checks that every atom was read, `line` is the line which ended the frame
+    fn check_rows(&self, dump_file_name: &Path, line: usize) -> Result<(), LammpsError> {
+        if (self.rows.len() as u64) < self.atoms {
+            return Err(LammpsError::TruncatedFrame {
+                path: dump_file_name.to_path_buf(),
+                line,
+                message: format!(
+                    "Frame of timestep {} ends at line {} after {} of {} atoms",
+                    self.timestep,
+                    line,
+                    self.rows.len(),
+                    self.atoms
+                ),
+            });
+        }
+        Ok(())
+    }
+
+    /// Parses the atoms into columns, which are i64 or f64 if every value is a number
+    fn to_df(&self, dump_file_name: &Path) -> Result<DataFrame, LammpsError> {
+        let parsed_data: Vec<Vec<&str>> = self
+            .rows
             .iter()
-            .map(|s| {
-                let mut split = s.split_whitespace();
-                (
-                    split.next().unwrap().parse::<f64>().unwrap(),
-                    split.next().unwrap().parse::<f64>().unwrap(),
-                )
+            .map(|(line_number, line)| {
+                let row: Vec<&str> = line.split_whitespace().collect();
+                if row.len() < self.columns.len() {
+                    return Err(LammpsError::TruncatedFrame {
+                        path: dump_file_name.to_path_buf(),
+                        line: *line_number,
+                        message: format!(
+                            "Atom at line {} has {} values but 'ITEM: ATOMS' at line {} has {} columns: '{}'",
+                            line_number,
+                            row.len(),
+                            self.header_line,
+                            self.columns.len(),
+                            line
+                        ),
+                    });
+                }
+                Ok(row)
             })
-            .unzip();
+            .collect::<Result<Vec<Vec<&str>>, LammpsError>>()?;
+
+        // Transpose data into columns
+        let mut columns: Vec<Column> = Vec::new();
+        for (col_idx, col_name) in self.columns.iter().enumerate() {
+            let col_data: Vec<&str> = parsed_data.iter().map(|row| row[col_idx]).collect();
+            let name: PlSmallStr = col_name.as_str().into();
+            let series = if let Ok(col_values) = parse_all::<i64>(&col_data) {
+                Column::new(name, col_values)
+            } else if let Ok(col_values) = parse_all::<f64>(&col_data) {
+                Column::new(name, col_values)
+            } else {
+                Column::new(name, col_data)
+            };
+            columns.push(series);
+        }
+        DataFrame::new(columns).map_err(|source| LammpsError::Polars {
+            path: dump_file_name.to_path_buf(),
+            source,
+        })
+    }
+}
+
+/** Iterator over the frames of a dump file, created by `DumpLammpsReader::iter_frames`.
+
+Yields the timestep, box and atoms of each frame. The iterator ends after the
+first error. */
+pub struct DumpFrameIter {
+    frame_reader: DumpFrameReader,
+    finished: bool,
+}
+
+impl Iterator for DumpFrameIter {
+    type Item = Result<(u64, DumpBox, DataFrame), LammpsError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        let frame: Result<Option<(u64, DumpBox, DataFrame)>, LammpsError> =
+            self.frame_reader.next_frame().and_then(|frame| {
+                let Some(frame) = frame else {
+                    return Ok(None);
+                };
+                let atoms: DataFrame = frame.to_df(&self.frame_reader.lines.dump_file_name)?;
+                Ok(Some((frame.timestep, frame.dump_box, atoms)))
+            });
+        match frame {
+            Ok(frame) => {
+                self.finished = frame.is_none();
+                frame.map(Ok)
+            }
+            Err(e) => {
+                self.finished = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Position of a frame in an uncompressed dump file
+struct DumpFrameOffset {
+    timestep: u64,
+    /// Byte offset of the first `ITEM:` line of the frame
+    offset: u64,
+    /// Line number of the first `ITEM:` line of the frame (starting at 1)
+    line: usize,
+}
+
+/** Index of the frames of a dump file, created by `DumpLammpsReader::index`.
 
-        let (zlo, zhi): (Vec<f64>, Vec<f64>) = z_bounds
+Frames are read by seeking to their byte offset, without reading the frames
+before them. */
+pub struct DumpFrameIndex {
+    dump_file_name: PathBuf,
+    frames: Vec<DumpFrameOffset>,
+}
+
+impl DumpFrameIndex {
+    /// Number of frames in the dump file
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    /// Timesteps of the frames in the order of the dump file
+    pub fn timesteps(&self) -> Vec<u64> {
+        self.frames.iter().map(|frame| frame.timestep).collect()
+    }
+
+    /** Reads a single frame.
+
+    ### Parameters:
+    frame_id: Index of the frame, starting at 0
+
+    Returns the timestep, box and atoms of the frame */
+    pub fn read_frame(&self, frame_id: usize) -> Result<(u64, DumpBox, DataFrame), LammpsError> {
+        let (mut frame_reader, mut frame) = self.read_header(frame_id)?;
+        frame_reader.read_atoms(&mut frame)?;
+        let atoms: DataFrame = frame.to_df(&self.dump_file_name)?;
+        Ok((frame.timestep, frame.dump_box, atoms))
+    }
+
+    /** Reads the box of a single frame without reading its atoms.
+
+    ### Parameters:
+    frame_id: Index of the frame, starting at 0
+
+    Returns the timestep and box of the frame */
+    pub fn read_box(&self, frame_id: usize) -> Result<(u64, DumpBox), LammpsError> {
+        let (_, frame) = self.read_header(frame_id)?;
+        Ok((frame.timestep, frame.dump_box))
+    }
+
+    /// Reads the sections of a frame up to its `ITEM: ATOMS` line
+    fn read_header(&self, frame_id: usize) -> Result<(DumpFrameReader, RawDumpFrame), LammpsError> {
+        let Some(frame_offset) = self.frames.get(frame_id) else {
+            return Err(LammpsError::FrameNotFound {
+                path: self.dump_file_name.clone(),
+                message: format!(
+                    "Frame {} not found, the dump file '{}' has {} frames",
+                    frame_id,
+                    self.dump_file_name.display(),
+                    self.frames.len()
+                ),
+            });
+        };
+        let mut dump_file: File = open_dump_file(&self.dump_file_name)?;
+        dump_file
+            .seek(SeekFrom::Start(frame_offset.offset))
+            .map_err(|source| LammpsError::Io {
+                path: self.dump_file_name.clone(),
+                source,
+            })?;
+        let mut frame_reader: DumpFrameReader = DumpFrameReader::new(
+            &self.dump_file_name,
+            Box::new(BufReader::new(dump_file)),
+            frame_offset.line - 1,
+        );
+        match frame_reader.next_header()? {
+            Some(frame) if frame.timestep == frame_offset.timestep => Ok((frame_reader, frame)),
+            _ => Err(LammpsError::InvalidData {
+                path: self.dump_file_name.clone(),
+                line: Some(frame_offset.line),
+                message: format!(
+                    "Frame of timestep {} not found at line {} of '{}', the index is out of date",
+                    frame_offset.timestep,
+                    frame_offset.line,
+                    self.dump_file_name.display()
+                ),
+            }),
+        }
+    }
+
+    /// Reads several frames in parallel, in the order of `frame_ids`
+    pub fn read_frames(
+        &self,
+        frame_ids: &[usize],
+    ) -> Result<Vec<(u64, DumpBox, DataFrame)>, LammpsError> {
+        frame_ids
+            .par_iter()
+            .map(|&frame_id| self.read_frame(frame_id))
+            .collect()
+    }
+
+    /// Reads the first frame of a timestep
+    pub fn read_timestep(&self, timestep: u64) -> Result<(u64, DumpBox, DataFrame), LammpsError> {
+        match self
+            .frames
             .iter()
-            .map(|s| {
-                let mut split = s.split_whitespace();
-                (
-                    split.next().unwrap().parse::<f64>().unwrap(),
-                    split.next().unwrap().parse::<f64>().unwrap(),
-                )
+            .position(|frame| frame.timestep == timestep)
+        {
+            Some(frame_id) => self.read_frame(frame_id),
+            None => Err(LammpsError::FrameNotFound {
+                path: self.dump_file_name.clone(),
+                message: format!(
+                    "Timestep {} not found in the dump file '{}'",
+                    timestep,
+                    self.dump_file_name.display()
+                ),
+            }),
+        }
+    }
+
+    /// Finds the frames of an uncompressed dump file, reading it line by line
+    fn scan(
+        dump_file_name: &Path,
+        mut dump_reader: impl BufRead,
+    ) -> Result<Vec<DumpFrameOffset>, LammpsError> {
+        let mut frames: Vec<DumpFrameOffset> = Vec::new();
+        let mut line_bytes: Vec<u8> = Vec::new();
+        let mut offset: u64 = 0;
+        let mut line: usize = 0;
+        let mut frame_start: Option<(u64, usize)> = None;
+        let mut timestep: Option<u64> = None;
+        // Set after an `ITEM: TIMESTEP` line, whose value is on the next line
+        let mut timestep_item: bool = false;
+        loop {
+            line_bytes.clear();
+            let read: usize = dump_reader
+                .read_until(b'\n', &mut line_bytes)
+                .map_err(|source| LammpsError::Io {
+                    path: dump_file_name.to_path_buf(),
+                    source,
+                })?;
+            if read == 0 && !timestep_item {
+                break;
+            }
+            let line_offset: u64 = offset;
+            offset += read as u64;
+            line += 1;
+
+            if timestep_item {
+                timestep_item = false;
+                let value = String::from_utf8_lossy(&line_bytes);
+                timestep =
+                    Some(
+                        value
+                            .trim()
+                            .parse()
+                            .map_err(|_| LammpsError::MalformedHeader {
+                                path: dump_file_name.to_path_buf(),
+                                line: Some(line),
+                                message: format!(
+                                    "Invalid value of 'ITEM: TIMESTEP' at line {}: '{}'",
+                                    line,
+                                    value.trim()
+                                ),
+                            })?,
+                    );
+                continue;
+            }
+            let Some(item) = line_bytes.strip_prefix(ITEM_FLAG.as_bytes()) else {
+                continue;
+            };
+            let (frame_offset, frame_line) = *frame_start.get_or_insert((line_offset, line));
+            let item = String::from_utf8_lossy(item);
+            let item: &str = item.trim();
+            if item.starts_with("TIMESTEP") {
+                timestep_item = true;
+            } else if item.starts_with("ATOMS") {
+                let Some(timestep) = timestep.take() else {
+                    return Err(LammpsError::MalformedHeader {
+                        path: dump_file_name.to_path_buf(),
+                        line: Some(line),
+                        message: format!(
+                            "'ITEM: ATOMS' of the frame at line {} does not follow an 'ITEM: TIMESTEP' section",
+                            frame_line
+                        ),
+                    });
+                };
+                frames.push(DumpFrameOffset {
+                    timestep,
+                    offset: frame_offset,
+                    line: frame_line,
+                });
+                frame_start = None;
+            }
+        }
+        Ok(frames)
+    }
+
+    /// Reads the index from its sidecar file, None if it does not match the dump file
+    fn read_sidecar(sidecar_file_name: &Path, stamp: &str) -> Option<Vec<DumpFrameOffset>> {
+        let sidecar: String = std::fs::read_to_string(sidecar_file_name).ok()?;
+        let mut lines = sidecar.lines();
+        if lines.next()? != SIDECAR_HEADER || lines.next()? != stamp {
+            return None;
+        }
+        lines
+            .map(|line| {
+                let mut values = line
+                    .split_whitespace()
+                    .map(|value| value.parse::<u64>().ok());
+                Some(DumpFrameOffset {
+                    timestep: values.next()??,
+                    offset: values.next()??,
+                    line: values.next()?? as usize,
+                })
             })
-            .unzip();
+            .collect()
+    }
 
-        self.box_state = df![
-            "timestep" => &self.timesteps,
-            "atoms" => atoms,
-            "xlo" => xlo,
-            "xhi" => xhi,
-            "ylo" => ylo,
-            "yhi" => yhi,
-            "zlo" => zlo,
-            "zhi" => zhi,
-        ]?;
+    /// Saves the index next to the dump file, along with the size and modification time of the dump file
+    fn write_sidecar(&self, sidecar_file_name: &Path, stamp: &str) -> std::io::Result<()> {
+        let mut sidecar: String = format!("{}\n{}\n", SIDECAR_HEADER, stamp);
+        for frame in &self.frames {
+            sidecar.push_str(&format!(
+                "{} {} {}\n",
+                frame.timestep, frame.offset, frame.line
+            ));
+        }
+        std::fs::write(sidecar_file_name, sidecar)
+    }
+}
 
-        Ok(())
+/// Reads the frames of a dump file one at a time
+struct DumpFrameReader {
+    lines: DumpLines,
+    /// `dump_modify units yes` prints the units of every frame, they cannot change
+    units: Option<String>,
+    found_item: bool,
+}
+
+impl DumpFrameReader {
+    fn open(dump_file_name: &Path) -> Result<DumpFrameReader, LammpsError> {
+        let dump_file: File = open_dump_file(dump_file_name)?;
+        let dump_reader: Box<dyn BufRead + Send> =
+            decompressed_reader(dump_file).map_err(|source| LammpsError::Io {
+                path: dump_file_name.to_path_buf(),
+                source,
+            })?;
+        Ok(DumpFrameReader::new(dump_file_name, dump_reader, 0))
     }
-    pub fn get_dump_map(&self) -> Result<BTreeMap<u64, DataFrame>, Box<dyn std::error::Error>> {
-        let data_map: BTreeMap<u64, DataFrame> = self
-            .timesteps
-            .iter()
-            .cloned()
-            .zip(self.trajectories.iter().cloned())
+
+    /// Reads the frames of a reader, after `line_number` lines of the dump file were skipped
+    fn new(
+        dump_file_name: &Path,
+        dump_reader: Box<dyn BufRead + Send>,
+        line_number: usize,
+    ) -> DumpFrameReader {
+        DumpFrameReader {
+            lines: DumpLines {
+                reader: dump_reader,
+                line_number,
+                dump_file_name: dump_file_name.to_path_buf(),
+                pending: None,
+            },
+            units: None,
+            found_item: false,
+        }
+    }
+
+    /// Reads the sections and atom lines of the next frame, None at the end of the file
+    fn next_frame(&mut self) -> Result<Option<RawDumpFrame>, LammpsError> {
+        let Some(mut frame) = self.next_header()? else {
+            return Ok(None);
+        };
+        self.read_atoms(&mut frame)?;
+        Ok(Some(frame))
+    }
+
+    /** Reads the sections of the next frame up to its `ITEM: ATOMS` line, None at the
+    end of the file. Its atoms must then be read with `read_atoms` or `skip_atoms`. */
+    fn next_header(&mut self) -> Result<Option<RawDumpFrame>, LammpsError> {
+        let lines: &mut DumpLines = &mut self.lines;
+        let mut timestep: Option<u64> = None;
+        let mut atoms: Option<u64> = None;
+        let mut dump_box: Option<DumpBox> = None;
+        let mut time: Option<f64> = None;
+        while let Some(line) = lines.next_line()? {
+            let Some(item) = line.strip_prefix(ITEM_FLAG) else {
+                // Values of sections which are not read
+                if self.found_item {
+                    continue;
+                }
+                return Err(LammpsError::UnsupportedFormat {
+                    path: lines.dump_file_name.clone(),
+                    message: format!(
+                        "Dump file at '{}' does not start with an 'ITEM:' line, line {}: '{}'",
+                        lines.dump_file_name.display(),
+                        lines.line_number,
+                        line
+                    ),
+                });
+            };
+            self.found_item = true;
+            let item: &str = item.trim();
+
+            if item.starts_with("TIMESTEP") {
+                timestep = Some(lines.next_value(item)?);
+            } else if item == "TIME" {
+                time = Some(lines.next_value(item)?);
+            } else if item == "UNITS" {
+                self.units = Some(lines.next_value(item)?);
+            } else if item.starts_with("NUMBER OF ATOMS") {
+                atoms = Some(lines.next_value(item)?);
+            } else if item.starts_with("BOX BOUNDS") {
+                dump_box = Some(lines.next_box(item)?);
+            } else if let Some(columns) = item.strip_prefix("ATOMS") {
+                let (Some(timestep), Some(atoms), Some(dump_box)) =
+                    (timestep, atoms, dump_box.take())
+                else {
+                    return Err(lines.malformed_header(
+                        "'ITEM: ATOMS' must follow the 'ITEM: TIMESTEP', 'ITEM: NUMBER OF ATOMS' and 'ITEM: BOX BOUNDS' sections of the frame".to_string(),
+                    ));
+                };
+                return Ok(Some(RawDumpFrame {
+                    timestep,
+                    atoms,
+                    dump_box,
+                    time,
+                    units: self.units.clone(),
+                    columns: columns.split_whitespace().map(String::from).collect(),
+                    header_line: lines.line_number,
+                    rows: Vec::new(),
+                }));
+            }
+        }
+
+        if !self.found_item {
+            return Err(LammpsError::UnsupportedFormat {
+                path: lines.dump_file_name.clone(),
+                message: format!(
+                    "No 'ITEM:' sections found in the dump file '{}'",
+                    lines.dump_file_name.display()
+                ),
+            });
+        }
+        if let Some(timestep) = timestep {
+            return Err(LammpsError::TruncatedFrame {
+                path: lines.dump_file_name.clone(),
+                line: lines.line_number + 1,
+                message: format!(
+                    "Dump file ended before the 'ITEM: ATOMS' section of timestep {}",
+                    timestep
+                ),
+            });
+        }
+        Ok(None)
+    }
+
+    /// Reads the atom lines of the frame returned by `next_header`
+    fn read_atoms(&mut self, frame: &mut RawDumpFrame) -> Result<(), LammpsError> {
+        let lines: &mut DumpLines = &mut self.lines;
+        // The atoms end at the next section or at the end of the file
+        let end_line: usize = loop {
+            let Some(line) = lines.next_line()? else {
+                break lines.line_number + 1;
+            };
+            if line.starts_with(ITEM_FLAG) {
+                lines.pending = Some(line);
+                break lines.line_number;
+            }
+            if !line.trim().is_empty() {
+                frame.rows.push((lines.line_number, line));
+            }
+        };
+        frame.check_rows(&lines.dump_file_name, end_line)
+    }
+
+    /// Steps over the atom lines of the frame returned by `next_header` without storing them
+    fn skip_atoms(&mut self) -> Result<(), LammpsError> {
+        let lines: &mut DumpLines = &mut self.lines;
+        let mut line: Vec<u8> = Vec::new();
+        loop {
+            line.clear();
+            let read: usize = lines
+                .reader
+                .read_until(b'\n', &mut line)
+                .map_err(|source| LammpsError::Io {
+                    path: lines.dump_file_name.clone(),
+                    source,
+                })?;
+            if read == 0 {
+                return Ok(());
+            }
+            lines.line_number += 1;
+            if line.starts_with(ITEM_FLAG.as_bytes()) {
+                let line: &[u8] = line.strip_suffix(b"\n").unwrap_or(&line);
+                let line: &[u8] = line.strip_suffix(b"\r").unwrap_or(line);
+                lines.pending = Some(String::from_utf8_lossy(line).into_owned());
+                return Ok(());
+            }
+        }
+    }
+}
+
+/// Lines of a dump file along with the number of the last line which was read
+struct DumpLines {
+    reader: Box<dyn BufRead + Send>,
+    line_number: usize,
+    dump_file_name: PathBuf,
+    /// A line which was read but belongs to the next frame
+    pending: Option<String>,
+}
+
+impl DumpLines {
+    fn next_line(&mut self) -> Result<Option<String>, LammpsError> {
+        if let Some(line) = self.pending.take() {
+            return Ok(Some(line));
+        }
+        let mut line: String = String::new();
+        let read: usize = self
+            .reader
+            .read_line(&mut line)
+            .map_err(|source| LammpsError::Io {
+                path: self.dump_file_name.clone(),
+                source,
+            })?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.line_number += 1;
+        if line.ends_with('\n') {
+            line.pop();
+            if line.ends_with('\r') {
+                line.pop();
+            }
+        }
+        Ok(Some(line))
+    }
+
+    /// Reads the x, y and z lines of an `ITEM: BOX BOUNDS` section
+    fn next_box(&mut self, item: &str) -> Result<DumpBox, LammpsError> {
+        let triclinic: bool = item.contains(TILT_FLAG);
+        // The boundary styles follow the tilt factors, old dumps do not have them
+        let styles: Vec<String> = item
+            .trim_start_matches("BOX BOUNDS")
+            .split_whitespace()
+            .filter(|style| !TILT_FLAG.split_whitespace().any(|tilt| tilt == *style))
+            .map(String::from)
             .collect();
+        let boundary: Option<[String; 3]> = styles.try_into().ok();
+        let mut bounds: [[f64; 3]; 3] = [[0.0; 3]; 3];
+        for axis_bounds in bounds.iter_mut() {
+            let values: Vec<f64> = self.next_values(item)?;
+            *axis_bounds = match values[..] {
+                [lo, hi, tilt] if triclinic => [lo, hi, tilt],
+                [lo, hi] if !triclinic => [lo, hi, 0.0],
+                _ => {
+                    return Err(self.malformed_header(format!(
+                        "Expected {} values for each axis of 'ITEM: {}' at line {}",
+                        if triclinic { 3 } else { 2 },
+                        item,
+                        self.line_number
+                    )));
+                }
+            };
+        }
+        Ok(DumpBox::from_bounds(bounds, triclinic, boundary))
+    }
 
-        Ok(data_map)
+    /// Reads the line of values which follows an `ITEM:` line
+    fn next_values<T: FromStr>(&mut self, item: &str) -> Result<Vec<T>, LammpsError> {
+        let Some(line) = self.next_line()? else {
+            return Err(LammpsError::TruncatedFrame {
+                path: self.dump_file_name.to_path_buf(),
+                line: self.line_number + 1,
+                message: format!(
+                    "Dump file ended before the value of 'ITEM: {}' at line {}",
+                    item, self.line_number
+                ),
+            });
+        };
+        parse_all::<T>(&line.split_whitespace().collect::<Vec<&str>>()).map_err(|_| {
+            self.malformed_header(format!(
+                "Invalid value of 'ITEM: {}' at line {}: '{}'",
+                item, self.line_number, line
+            ))
+        })
+    }
+
+    /// Reads the single value which follows an `ITEM:` line
+    fn next_value<T: FromStr>(&mut self, item: &str) -> Result<T, LammpsError> {
+        let mut values: Vec<T> = self.next_values(item)?;
+        match values.len() {
+            1 => Ok(values.remove(0)),
+            _ => Err(self.malformed_header(format!(
+                "Expected a single value of 'ITEM: {}' at line {}",
+                item, self.line_number
+            ))),
+        }
+    }
+
+    fn malformed_header(&self, message: String) -> LammpsError {
+        LammpsError::MalformedHeader {
+            path: self.dump_file_name.to_path_buf(),
+            line: Some(self.line_number),
+            message,
+        }
+    }
+}
+
+/// Opens a dump file
+fn open_dump_file(dump_file_name: &Path) -> Result<File, LammpsError> {
+    File::open(dump_file_name).map_err(|error| {
+        LammpsError::from_open(
+            dump_file_name,
+            error,
+            &format!(
+                "Dump file at '{}' not found...\nCheck 'dump_file_name' parameter",
+                dump_file_name.display()
+            ),
+        )
+    })
+}
+
+/// Parses every value, or returns the error of the first one which is invalid
+fn parse_all<T: FromStr>(values: &[&str]) -> Result<Vec<T>, T::Err> {
+    values.iter().map(|value| value.parse::<T>()).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Writes a dump file in the temporary directory
+    fn write_dump(name: &str, dump: &str) -> PathBuf {
+        let dump_file_name: PathBuf = std::env::temp_dir().join(format!(
+            "log_lammps_reader_{}_{}.dump",
+            std::process::id(),
+            name
+        ));
+        std::fs::write(&dump_file_name, dump).unwrap();
+        dump_file_name
+    }
+
+    /// A frame of three atoms, `box_bounds` are the lines from `ITEM: BOX BOUNDS`
+    fn dump_frame(timestep: u64, box_bounds: &str) -> String {
+        format!(
+            "ITEM: TIMESTEP\n{timestep}\nITEM: NUMBER OF ATOMS\n3\nITEM: BOX BOUNDS {box_bounds}
+ITEM: ATOMS id type x y z\n1 1 1.0 2.0 {timestep}.5\n2 1 4.0 5.0 6.0\n3 2 7.0 8.0 9.0\n"
+        )
+    }
+
+    #[test]
+    fn converts_triclinic_bounds_to_cell() {
+        let dump_box: DumpBox = DumpBox::from_bounds(
+            [[-1.0, 12.0, 2.0], [0.0, 11.0, -1.0], [0.0, 10.0, 1.0]],
+            true,
+            None,
+        );
+        assert_eq!(dump_box.lo, [0.0, 0.0, 0.0]);
+        assert_eq!(dump_box.hi, [10.0, 10.0, 10.0]);
+        assert_eq!(dump_box.tilt, [2.0, -1.0, 1.0]);
+        assert_eq!(
+            dump_box.cell_matrix(),
+            [[10.0, 0.0, 0.0], [2.0, 10.0, 0.0], [-1.0, 1.0, 10.0]]
+        );
+
+        let dump_box: DumpBox = DumpBox::from_bounds(
+            [[-1.0, 12.0, 0.0], [0.0, 11.0, 0.0], [0.0, 10.0, 0.0]],
+            false,
+            None,
+        );
+        assert_eq!(dump_box.lo, [-1.0, 0.0, 0.0]);
+        assert_eq!(dump_box.hi, [12.0, 11.0, 10.0]);
+        assert_eq!(dump_box.lengths(), [13.0, 11.0, 10.0]);
+        assert_eq!(dump_box.tilt, [0.0; 3]);
+    }
+
+    #[test]
+    fn reads_triclinic_box_bounds() {
+        let dump: String = [
+            dump_frame(0, "xy xz yz pp pp fm\n-1 12 2\n0 11 -1\n0 10 1"),
+            dump_frame(10, "pp pp pp\n0 10\n0 10\n0 10"),
+        ]
+        .concat();
+        let dump_file_name: PathBuf = write_dump("triclinic", &dump);
+        let frames: Vec<(u64, DumpBox, DataFrame)> = DumpLammpsReader::iter_frames(dump_file_name)
+            .unwrap()
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(frames.len(), 2);
+        let (_, triclinic_box, atoms) = &frames[0];
+        assert!(triclinic_box.triclinic);
+        assert_eq!(triclinic_box.lengths(), [10.0, 10.0, 10.0]);
+        assert_eq!(triclinic_box.tilt, [2.0, -1.0, 1.0]);
+        assert_eq!(
+            triclinic_box.boundary,
+            Some(["pp".to_string(), "pp".to_string(), "fm".to_string()])
+        );
+        assert_eq!(atoms.height(), 3);
+        let (_, orthogonal_box, _) = &frames[1];
+        assert!(!orthogonal_box.triclinic);
+        assert_eq!(orthogonal_box.tilt, [0.0; 3]);
+    }
+
+    #[test]
+    fn index_round_trips_through_sidecar() {
+        let dump: String = [
+            dump_frame(0, "pp pp pp\n0 10\n0 10\n0 10"),
+            dump_frame(50, "xy xz yz pp pp pp\n-1 12 2\n0 11 -1\n0 10 1"),
+            dump_frame(100, "pp pp pp\n0 10\n0 10\n0 10"),
+        ]
+        .concat();
+        let dump_file_name: PathBuf = write_dump("index", &dump);
+        let sidecar_file_name: PathBuf =
+            format!("{}.{}", dump_file_name.display(), SIDECAR_EXTENSION).into();
+        let _ = std::fs::remove_file(&sidecar_file_name);
+        let offsets = |index: &DumpFrameIndex| -> Vec<(u64, u64, usize)> {
+            index
+                .frames
+                .iter()
+                .map(|frame| (frame.timestep, frame.offset, frame.line))
+                .collect()
+        };
+
+        let index: DumpFrameIndex = DumpLammpsReader::index(dump_file_name.clone(), true).unwrap();
+        assert_eq!(index.timesteps(), [0, 50, 100]);
+        let frames: Vec<(u64, DumpBox, DataFrame)> =
+            DumpLammpsReader::iter_frames(dump_file_name.clone())
+                .unwrap()
+                .collect::<Result<_, _>>()
+                .unwrap();
+        for (frame_id, (timestep, dump_box, atoms)) in frames.iter().enumerate().rev() {
+            let (read_timestep, read_box, read_atoms) = index.read_frame(frame_id).unwrap();
+            assert_eq!((read_timestep, &read_box), (*timestep, dump_box));
+            assert!(read_atoms.equals_missing(atoms));
+            assert_eq!(
+                index.read_box(frame_id).unwrap(),
+                (*timestep, dump_box.clone())
+            );
+        }
+        assert!(index.read_frame(3).is_err());
+
+        // The sidecar stores the offsets along with the size and modification time
+        let sidecar: String = std::fs::read_to_string(&sidecar_file_name).unwrap();
+        let stamp: &str = sidecar.lines().nth(1).unwrap();
+        let sidecar_frames: Vec<DumpFrameOffset> =
+            DumpFrameIndex::read_sidecar(&sidecar_file_name, stamp).unwrap();
+        let sidecar_index = DumpFrameIndex {
+            dump_file_name: dump_file_name.clone(),
+            frames: sidecar_frames,
+        };
+        assert_eq!(offsets(&sidecar_index), offsets(&index));
+        assert!(DumpFrameIndex::read_sidecar(&sidecar_file_name, "size 0 modified 0").is_none());
+
+        // A dump file which is rewritten with the same size is scanned again
+        let rewritten: String = dump.replace("TIMESTEP\n50\n", "TIMESTEP\n60\n");
+        assert_eq!(rewritten.len(), dump.len());
+        std::thread::sleep(std::time::Duration::from_millis(10));
+        std::fs::write(&dump_file_name, rewritten).unwrap();
+        let index: DumpFrameIndex = DumpLammpsReader::index(dump_file_name.clone(), true).unwrap();
+        assert_eq!(index.timesteps(), [0, 60, 100]);
+
+        // The sidecar is only a cache, the index is still returned if it cannot be written
+        std::fs::remove_file(&sidecar_file_name).unwrap();
+        std::fs::create_dir(&sidecar_file_name).unwrap();
+        let index: DumpFrameIndex = DumpLammpsReader::index(dump_file_name, true).unwrap();
+        assert_eq!(index.len(), 3);
+        std::fs::remove_dir(&sidecar_file_name).unwrap();
+    }
+
+    #[test]
+    fn index_reports_line_of_invalid_timestep() {
+        let dump: String = [
+            dump_frame(0, "pp pp pp\n0 10\n0 10\n0 10"),
+            dump_frame(50, "pp pp pp\n0 10\n0 10\n0 10"),
+        ]
+        .concat()
+        .replace("TIMESTEP\n50\n", "TIMESTEP\n5x\n");
+        let dump_file_name: PathBuf = write_dump("invalid_timestep", &dump);
+        match DumpLammpsReader::index(dump_file_name, false) {
+            Err(LammpsError::MalformedHeader { line, .. }) => assert_eq!(line, Some(14)),
+            Err(e) => panic!("unexpected error: {e}"),
+            Ok(_) => panic!("the invalid timestep was not reported"),
+        }
     }
 }
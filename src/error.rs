@@ -0,0 +1,210 @@
+use crate::log_reader::ThermoParseError;
+use polars::prelude::PolarsError;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+/// Errors returned by the log and dump readers
+#[derive(Debug)]
+pub enum LammpsError {
+    /// The file could not be opened
+    FileNotFound { path: PathBuf, message: String },
+    /// The file could not be read
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The file is not a LAMMPS log or dump file, or has no data which can be read
+    UnsupportedFormat { path: PathBuf, message: String },
+    /// A thermo header or a dump `ITEM:` section could not be parsed
+    MalformedHeader {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+    /// The file ended, or the next section started, before a dump frame was complete
+    TruncatedFrame {
+        path: PathBuf,
+        line: usize,
+        message: String,
+    },
+    /// The requested thermo run does not exist or has no data
+    RunNotFound {
+        path: PathBuf,
+        run_id: u32,
+        message: String,
+    },
+    /// The requested dump frame or timestep does not exist
+    FrameNotFound { path: PathBuf, message: String },
+    /// Values of the file which could not be parsed
+    InvalidData {
+        path: PathBuf,
+        line: Option<usize>,
+        message: String,
+    },
+    /// The data could not be converted into a polars DataFrame
+    Polars { path: PathBuf, source: PolarsError },
+}
+
+impl LammpsError {
+    /// File path of the log or dump file which caused the error
+    pub fn path(&self) -> &Path {
+        match self {
+            LammpsError::FileNotFound { path, .. }
+            | LammpsError::Io { path, .. }
+            | LammpsError::UnsupportedFormat { path, .. }
+            | LammpsError::MalformedHeader { path, .. }
+            | LammpsError::TruncatedFrame { path, .. }
+            | LammpsError::RunNotFound { path, .. }
+            | LammpsError::FrameNotFound { path, .. }
+            | LammpsError::InvalidData { path, .. }
+            | LammpsError::Polars { path, .. } => path,
+        }
+    }
+
+    /// Line number of the file which caused the error (starting at 1), if known
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            LammpsError::MalformedHeader { line, .. } | LammpsError::InvalidData { line, .. } => {
+                *line
+            }
+            LammpsError::TruncatedFrame { line, .. } => Some(*line),
+            _ => None,
+        }
+    }
+
+    /** Converts the error of opening a file. A missing file is `FileNotFound` with
+    the `not_found` message, other errors (e.g. permission denied) are `Io`. */
+    pub fn from_open(path: &Path, error: std::io::Error, not_found: &str) -> LammpsError {
+        match error.kind() {
+            std::io::ErrorKind::NotFound => LammpsError::FileNotFound {
+                path: path.to_path_buf(),
+                message: not_found.to_string(),
+            },
+            _ => LammpsError::Io {
+                path: path.to_path_buf(),
+                source: error,
+            },
+        }
+    }
+
+    /** Converts an error of the parsers into a LammpsError of a file.
+
+    Errors which are already a LammpsError are kept, thermo parse errors keep
+    their line, I/O and polars errors are wrapped and any other error becomes
+    `InvalidData`. */
+    pub fn from_boxed(path: &Path, error: Box<dyn std::error::Error>) -> LammpsError {
+        let error = match error.downcast::<LammpsError>() {
+            Ok(error) => return *error,
+            Err(error) => error,
+        };
+        let error = match error.downcast::<ThermoParseError>() {
+            Ok(error) => {
+                let message: String = error.to_string();
+                return match *error {
+                    ThermoParseError::MissingHeader { line, .. } => LammpsError::MalformedHeader {
+                        path: path.to_path_buf(),
+                        line: Some(line),
+                        message,
+                    },
+                    ThermoParseError::NoValidRows { line, .. } => LammpsError::InvalidData {
+                        path: path.to_path_buf(),
+                        line: Some(line),
+                        message,
+                    },
+                };
+            }
+            Err(error) => error,
+        };
+        let error = match error.downcast::<std::io::Error>() {
+            Ok(error) if error.kind() == std::io::ErrorKind::NotFound => {
+                return LammpsError::FileNotFound {
+                    path: path.to_path_buf(),
+                    message: format!("File at '{}' not found: {}", path.display(), error),
+                };
+            }
+            Ok(error) => {
+                return LammpsError::Io {
+                    path: path.to_path_buf(),
+                    source: *error,
+                };
+            }
+            Err(error) => error,
+        };
+        match error.downcast::<PolarsError>() {
+            Ok(error) => LammpsError::Polars {
+                path: path.to_path_buf(),
+                source: *error,
+            },
+            Err(error) => LammpsError::InvalidData {
+                path: path.to_path_buf(),
+                line: None,
+                message: error.to_string(),
+            },
+        }
+    }
+
+    /** Converts the error of collecting the LazyFrame of `LogLammpsReader::scan`.
+
+    The errors of the parser are passed through polars as I/O errors, and are taken
+    out of them again. Any other error is `Polars`. */
+    pub fn from_polars(path: &Path, error: PolarsError) -> LammpsError {
+        match error {
+            PolarsError::Context { error, msg } => match LammpsError::from_polars(path, *error) {
+                LammpsError::Polars { path, source } => LammpsError::Polars {
+                    path,
+                    source: PolarsError::Context {
+                        error: Box::new(source),
+                        msg,
+                    },
+                },
+                error => error,
+            },
+            PolarsError::IO { error, msg } => {
+                let error: Arc<std::io::Error> =
+                    match Arc::try_unwrap(error).map(std::io::Error::downcast::<LammpsError>) {
+                        Ok(Ok(error)) => return error,
+                        Ok(Err(error)) => Arc::new(error),
+                        Err(error) => error,
+                    };
+                LammpsError::Polars {
+                    path: path.to_path_buf(),
+                    source: PolarsError::IO { error, msg },
+                }
+            }
+            error => LammpsError::Polars {
+                path: path.to_path_buf(),
+                source: error,
+            },
+        }
+    }
+}
+
+impl std::fmt::Display for LammpsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            LammpsError::FileNotFound { message, .. }
+            | LammpsError::UnsupportedFormat { message, .. }
+            | LammpsError::MalformedHeader { message, .. }
+            | LammpsError::TruncatedFrame { message, .. }
+            | LammpsError::RunNotFound { message, .. }
+            | LammpsError::FrameNotFound { message, .. }
+            | LammpsError::InvalidData { message, .. } => write!(f, "{}", message),
+            LammpsError::Io { path, source } => {
+                write!(f, "Failed to read '{}': {}", path.display(), source)
+            }
+            LammpsError::Polars { path, source } => {
+                write!(f, "Failed to convert '{}': {}", path.display(), source)
+            }
+        }
+    }
+}
+
+impl std::error::Error for LammpsError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            LammpsError::Io { source, .. } => Some(source),
+            LammpsError::Polars { source, .. } => Some(source),
+            _ => None,
+        }
+    }
+}
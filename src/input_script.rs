@@ -0,0 +1,468 @@
+use polars::prelude::*;
+use regex::Regex;
+use std::collections::{HashMap, VecDeque};
+use std::sync::LazyLock;
+
+/// Matches `${name}`, `$(expression)` and the single character `$x` references
+static VARIABLE_REFERENCE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"\$\{(?P<name>[^}]*)\}|\$\((?P<expression>[^)]*)\)|\$(?P<short>[A-Za-z0-9_])")
+        .expect("valid variable reference regex")
+});
+
+/// Input script commands of LAMMPS, which are echoed to the log file as they are read
+const COMMANDS: &[&str] = &[
+    "angle_coeff",
+    "angle_style",
+    "angle_write",
+    "atom_modify",
+    "atom_style",
+    "balance",
+    "bond_coeff",
+    "bond_style",
+    "bond_write",
+    "boundary",
+    "change_box",
+    "clear",
+    "comm_modify",
+    "comm_style",
+    "compute",
+    "compute_modify",
+    "create_atoms",
+    "create_bonds",
+    "create_box",
+    "delete_atoms",
+    "delete_bonds",
+    "dielectric",
+    "dihedral_coeff",
+    "dihedral_style",
+    "dihedral_write",
+    "dimension",
+    "displace_atoms",
+    "dump",
+    "dump_modify",
+    "echo",
+    "fix",
+    "fix_modify",
+    "group",
+    "if",
+    "improper_coeff",
+    "improper_style",
+    "include",
+    "info",
+    "jump",
+    "kspace_modify",
+    "kspace_style",
+    "label",
+    "labelmap",
+    "lattice",
+    "log",
+    "mass",
+    "min_modify",
+    "min_style",
+    "minimize",
+    "molecule",
+    "neb",
+    "neigh_modify",
+    "neighbor",
+    "newton",
+    "next",
+    "package",
+    "pair_coeff",
+    "pair_modify",
+    "pair_style",
+    "pair_write",
+    "partition",
+    "plugin",
+    "print",
+    "processors",
+    "python",
+    "quit",
+    "read_data",
+    "read_dump",
+    "read_restart",
+    "region",
+    "replicate",
+    "rerun",
+    "reset_atoms",
+    "reset_timestep",
+    "restart",
+    "run",
+    "run_style",
+    "set",
+    "shell",
+    "special_bonds",
+    "suffix",
+    "thermo",
+    "thermo_modify",
+    "thermo_style",
+    "timer",
+    "timestep",
+    "uncompute",
+    "undump",
+    "unfix",
+    "units",
+    "variable",
+    "velocity",
+    "write_coeff",
+    "write_data",
+    "write_dump",
+    "write_restart",
+];
+
+/// A command of the input script as it was echoed in the log file
+#[derive(Clone, Debug, PartialEq)]
+pub struct InputCommand {
+    /// Line number of the command in the log file, starting at 1
+    pub line: usize,
+    /// The thermo run which follows the command
+    pub run_id: u32,
+    /// The command as it was written in the input script
+    pub raw: String,
+    /// The command after the variables were substituted
+    pub substituted: String,
+}
+
+impl InputCommand {
+    /// Returns a polars DataFrame with one row for each command
+    pub fn to_df(commands: &[InputCommand]) -> PolarsResult<DataFrame> {
+        df![
+            "line" => commands.iter().map(|command| command.line as u64).collect::<Vec<u64>>(),
+            "run_id" => commands.iter().map(|command| command.run_id).collect::<Vec<u32>>(),
+            "raw" => commands.iter().map(|command| command.raw.as_str()).collect::<Vec<&str>>(),
+            "substituted" => commands
+                .iter()
+                .map(|command| command.substituted.as_str())
+                .collect::<Vec<&str>>(),
+        ]
+    }
+
+    /** Returns a regex which matches the command after some or all of its
+    variables were substituted. */
+    fn substitution_regex(&self) -> Option<Regex> {
+        let mut pattern: String = String::from("^");
+        let mut literal_start: usize = 0;
+        for reference in VARIABLE_REFERENCE.find_iter(&self.raw) {
+            pattern.push_str(&regex::escape(&self.raw[literal_start..reference.start()]));
+            pattern.push_str(".*?");
+            literal_start = reference.end();
+        }
+        pattern.push_str(&regex::escape(&self.raw[literal_start..]));
+        pattern.push('$');
+        Regex::new(&pattern).ok()
+    }
+}
+
+/** Rebuilds the input script from the commands echoed in the log file.
+
+LAMMPS echoes every command before its variables are substituted, followed by the
+command after the substitution. Both forms are joined into a single command, and
+`variable` and `next` commands are tracked to substitute the variables which LAMMPS
+did not echo. Lines which are not commands (e.g. the output of a command) are ignored. */
+#[derive(Default)]
+pub struct InputScript {
+    commands: Vec<InputCommand>,
+    /// The command which may still be followed by its substituted form
+    pending: Option<(InputCommand, Option<Regex>)>,
+    /// Defined variables
+    variables: HashMap<String, Variable>,
+}
+
+impl InputScript {
+    /// Adds a line of the log file outside of the thermo output
+    pub fn add_line(&mut self, line_number: usize, run_id: u32, line: &str) {
+        let line: &str = line.trim();
+        if is_command_output(line) {
+            self.finish_pending();
+            return;
+        }
+        if let Some((command, substitution)) = self.pending.as_mut()
+            && substitution
+                .as_ref()
+                .is_some_and(|substitution| substitution.is_match(line))
+        {
+            command.substituted = line.to_string();
+            return;
+        }
+        self.finish_pending();
+
+        let Some(name) = line.split_whitespace().next() else {
+            return;
+        };
+        if !COMMANDS.contains(&name) {
+            return;
+        }
+        let command = InputCommand {
+            line: line_number,
+            run_id,
+            raw: line.to_string(),
+            substituted: line.to_string(),
+        };
+        let substitution: Option<Regex> = if line.contains('$') {
+            command.substitution_regex()
+        } else {
+            None
+        };
+        self.pending = Some((command, substitution));
+    }
+
+    /// Returns the commands in the order they were read
+    pub fn finish(mut self) -> Vec<InputCommand> {
+        self.finish_pending();
+        self.commands
+    }
+
+    fn finish_pending(&mut self) {
+        let Some((mut command, _)) = self.pending.take() else {
+            return;
+        };
+        if command.substituted.contains('$') {
+            command.substituted = self.substitute(&command.substituted);
+        }
+        let tokens: Vec<&str> = command.substituted.split_whitespace().collect();
+        match tokens.as_slice() {
+            ["variable", name, style, values @ ..] => match *style {
+                "delete" => {
+                    self.variables.remove(*name);
+                }
+                // LAMMPS ignores these definitions if the variable already exists, so that
+                // `-var` settings of the command line take precedence. Other styles replace it
+                "index" | "loop" | "world" | "universe" | "uloop"
+                    if self.variables.contains_key(*name) => {}
+                _ => {
+                    self.variables
+                        .insert(name.to_string(), Variable::new(style, values));
+                }
+            },
+            // A variable without more values is deleted, so that it can be defined again
+            ["next", names @ ..] => {
+                for name in names {
+                    if let Some(variable) = self.variables.get_mut(*name)
+                        && !variable.advance()
+                    {
+                        self.variables.remove(*name);
+                    }
+                }
+            }
+            _ => {}
+        }
+        self.commands.push(command);
+    }
+
+    /// Substitutes the `${name}` and `$x` references of the variables which are known
+    fn substitute(&self, text: &str) -> String {
+        VARIABLE_REFERENCE
+            .replace_all(text, |captures: &regex::Captures| {
+                let name = captures
+                    .name("name")
+                    .or_else(|| captures.name("short"))
+                    .map(|name| name.as_str());
+                match name.and_then(|name| self.variables.get(name)?.value.as_ref()) {
+                    Some(value) => value.clone(),
+                    None => captures[0].to_string(),
+                }
+            })
+            .into_owned()
+    }
+}
+
+/// A variable defined by `variable name style values`
+struct Variable {
+    /// The current value, None if it is not known (e.g. a formula)
+    value: Option<String>,
+    /// The values which the `next` command moves through
+    next_values: NextValues,
+}
+
+/// The values of a variable after its current value
+enum NextValues {
+    /// Styles which are not changed by `next`
+    Fixed,
+    /// The remaining values of an `index`, `universe` or `uloop` variable
+    List(VecDeque<String>),
+    /// The remaining values of a `loop` variable, padded with zeros to `width`
+    Loop { next: i64, stop: i64, width: usize },
+}
+
+impl Variable {
+    fn new(style: &str, values: &[&str]) -> Variable {
+        let fixed = |value: Option<String>| Variable {
+            value,
+            next_values: NextValues::Fixed,
+        };
+        match style {
+            "index" | "universe" => {
+                let mut values: VecDeque<String> =
+                    values.iter().map(|value| value.to_string()).collect();
+                Variable {
+                    value: values.pop_front(),
+                    next_values: NextValues::List(values),
+                }
+            }
+            "world" => fixed(values.first().map(|value| value.to_string())),
+            "string" => fixed(Some(values.join(" ").trim_matches('"').to_string())),
+            // `loop N` and `uloop N` go from 1 to N, `loop N1 N2` from N1 to N2
+            "loop" | "uloop" => {
+                let pad: bool = values.last() == Some(&"pad");
+                let bounds: Vec<i64> = values
+                    .iter()
+                    .map_while(|value| value.parse::<i64>().ok())
+                    .collect();
+                let (start, stop): (i64, i64) = match bounds.as_slice() {
+                    [stop] => (1, *stop),
+                    [start, stop, ..] if style == "loop" => (*start, *stop),
+                    _ => return fixed(None),
+                };
+                let width: usize = if pad { stop.to_string().len() } else { 0 };
+                Variable {
+                    value: Some(format!("{:0width$}", start, width = width)),
+                    next_values: NextValues::Loop {
+                        next: start + 1,
+                        stop,
+                        width,
+                    },
+                }
+            }
+            // Formulas which are only a number
+            "equal" | "internal" => match values {
+                [value] if value.parse::<f64>().is_ok() => fixed(Some(value.to_string())),
+                _ => fixed(None),
+            },
+            _ => fixed(None),
+        }
+    }
+
+    /// Moves to the next value for the `next` command, false if there are no more values
+    fn advance(&mut self) -> bool {
+        let value: Option<String> = match &mut self.next_values {
+            NextValues::Fixed => return true,
+            NextValues::List(values) => values.pop_front(),
+            NextValues::Loop { next, stop, width } => (*next <= *stop).then(|| {
+                *next += 1;
+                format!("{:0width$}", *next - 1, width = *width)
+            }),
+        };
+        match value {
+            Some(value) => {
+                self.value = Some(value);
+                true
+            }
+            None => false,
+        }
+    }
+}
+
+/// Checks if a line is the timing output of a command, such as `read_data CPU = 0.002 seconds`
+fn is_command_output(line: &str) -> bool {
+    let mut tokens = line.split_whitespace().skip(1);
+    tokens.next() == Some("CPU") && tokens.next() == Some("=")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn rebuild(lines: &[&str]) -> Vec<InputCommand> {
+        let mut script = InputScript::default();
+        for (index, line) in lines.iter().enumerate() {
+            script.add_line(index + 1, 0, line);
+        }
+        script.finish()
+    }
+
+    fn substituted(commands: &[InputCommand]) -> Vec<&str> {
+        commands
+            .iter()
+            .map(|command| command.substituted.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn joins_echoed_substitution_and_substitutes_the_rest() {
+        let commands = rebuild(&[
+            "variable T equal 300",
+            "variable name string \"melt\"",
+            "fix 1 all nvt temp ${T} ${T} 0.1",
+            "fix 1 all nvt temp 300 300 0.1",
+            "dump 1 all atom 100 ${name}.$T.dump",
+        ]);
+        assert_eq!(
+            substituted(&commands),
+            [
+                "variable T equal 300",
+                "variable name string \"melt\"",
+                "fix 1 all nvt temp 300 300 0.1",
+                "dump 1 all atom 100 melt.300.dump",
+            ]
+        );
+        assert_eq!(commands[2].raw, "fix 1 all nvt temp ${T} ${T} 0.1");
+        assert_eq!(commands[2].line, 3);
+        assert_eq!(commands[3].line, 5);
+    }
+
+    #[test]
+    fn keeps_index_variables_and_replaces_other_styles() {
+        let commands = rebuild(&[
+            "variable a index 1 2",
+            "variable a index 5",
+            "variable s string x",
+            "variable s string y",
+            "print \"${a} ${s} ${unknown}\"",
+            "variable a delete",
+            "variable a index 7",
+            "print \"${a}\"",
+        ]);
+        assert_eq!(commands[4].substituted, "print \"1 y ${unknown}\"");
+        assert_eq!(commands[7].substituted, "print \"7\"");
+    }
+
+    #[test]
+    fn next_advances_loop_variables() {
+        let mut lines: Vec<&str> = vec!["variable i loop 2 3 pad", "variable j index a b"];
+        for _ in 0..3 {
+            lines.extend([
+                "label loop",
+                "print \"${i} ${j}\"",
+                "next i j",
+                "jump SELF loop",
+            ]);
+        }
+        lines.push("variable i loop 4");
+        lines.push("print \"${i}\"");
+        let commands = rebuild(&lines);
+        let prints: Vec<&str> = substituted(&commands)
+            .into_iter()
+            .filter(|command| command.starts_with("print"))
+            .collect();
+        // Both variables are deleted after their last value, which allows a new definition
+        assert_eq!(
+            prints,
+            [
+                "print \"2 a\"",
+                "print \"3 b\"",
+                "print \"${i} ${j}\"",
+                "print \"1\""
+            ]
+        );
+    }
+
+    #[test]
+    fn ignores_command_output_lines() {
+        let commands = rebuild(&[
+            "variable file string data.lmp",
+            "read_data ${file}",
+            "Reading data file ...",
+            "  read_data CPU = 0.002 seconds",
+            "create_atoms 1 box",
+            "Created 4000 atoms",
+            "  create_atoms CPU = 0.000 seconds",
+        ]);
+        assert_eq!(
+            substituted(&commands),
+            [
+                "variable file string data.lmp",
+                "read_data data.lmp",
+                "create_atoms 1 box"
+            ]
+        );
+    }
+}
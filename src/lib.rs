@@ -1,31 +1,687 @@
+use polars::prelude::{DataFrame, Expr, Field, IdxSize, LazyFrame, Schema, col};
+use pyo3::create_exception;
+use pyo3::exceptions::{PyIndexError, PyUserWarning, PyValueError};
 use pyo3::prelude::*;
-use pyo3_polars::PyDataFrame;
-use std::collections::BTreeMap;
+use pyo3::types::{PyDict, PyList, PySlice};
+use pyo3_polars::{PyDataFrame, PyDataType, PyExpr};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::ffi::CString;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
 
 mod analyze;
+mod compression;
 mod dump_reader;
+mod error;
+mod input_script;
 mod log_reader;
+mod metadata;
+mod run_stats;
+mod timeline;
 use analyze::AnalyzeLammps;
-use dump_reader::DumpLammpsReader;
-use log_reader::LogLammpsReader;
+use dump_reader::{DumpFrameIndex, DumpFrameIter, DumpLammpsReader, DumpSelection};
+use error::LammpsError;
+pub use log_reader::LogLammpsReader;
+use log_reader::{SkippedRow, ThermoUpdate};
+use metadata::SimulationSetup;
+
+create_exception!(
+    log_lammps_reader,
+    LammpsReaderError,
+    pyo3::exceptions::PyException,
+    "Base class of the errors raised while reading LAMMPS files, with the `path` and `line` of the error."
+);
+create_exception!(
+    log_lammps_reader,
+    LammpsFileNotFoundError,
+    LammpsReaderError,
+    "The log or dump file could not be opened."
+);
+create_exception!(
+    log_lammps_reader,
+    UnsupportedFormatError,
+    LammpsReaderError,
+    "The file is not a LAMMPS log or dump file, or has no data which can be read."
+);
+create_exception!(
+    log_lammps_reader,
+    MalformedHeaderError,
+    LammpsReaderError,
+    "A thermo header or a dump `ITEM:` section could not be parsed."
+);
+create_exception!(
+    log_lammps_reader,
+    TruncatedFrameError,
+    LammpsReaderError,
+    "A dump frame ended before all of its atoms or values were read."
+);
+create_exception!(
+    log_lammps_reader,
+    RunNotFoundError,
+    LammpsReaderError,
+    "The requested thermo run does not exist or has no data."
+);
+create_exception!(
+    log_lammps_reader,
+    FrameNotFoundError,
+    LammpsReaderError,
+    "The requested dump frame or timestep does not exist."
+);
+create_exception!(
+    log_lammps_reader,
+    InvalidDataError,
+    LammpsReaderError,
+    "Values of the file could not be parsed."
+);
+
+/** Converts a LammpsError into the matching Python exception, with the `path`
+of the file and the `line` of the error (None if it is not known). */
+fn reader_error(reader: &str, e: LammpsError) -> PyErr {
+    let message: String = format!("{} error: {}", reader, e);
+    let err: PyErr = match &e {
+        LammpsError::FileNotFound { .. } => PyErr::new::<LammpsFileNotFoundError, _>(message),
+        LammpsError::UnsupportedFormat { .. } => PyErr::new::<UnsupportedFormatError, _>(message),
+        LammpsError::MalformedHeader { .. } => PyErr::new::<MalformedHeaderError, _>(message),
+        LammpsError::TruncatedFrame { .. } => PyErr::new::<TruncatedFrameError, _>(message),
+        LammpsError::RunNotFound { .. } => PyErr::new::<RunNotFoundError, _>(message),
+        LammpsError::FrameNotFound { .. } => PyErr::new::<FrameNotFoundError, _>(message),
+        LammpsError::InvalidData { .. } => PyErr::new::<InvalidDataError, _>(message),
+        LammpsError::Io { .. } | LammpsError::Polars { .. } => {
+            PyErr::new::<LammpsReaderError, _>(message)
+        }
+    };
+    Python::with_gil(|py| {
+        let value = err.value(py);
+        value.setattr("path", e.path().display().to_string())?;
+        value.setattr("line", e.line())?;
+        if let LammpsError::RunNotFound { run_id, .. } = &e {
+            value.setattr("run_id", run_id)?;
+        }
+        Ok::<(), PyErr>(())
+    })
+    .map_or_else(|setattr_err| setattr_err, |_| err)
+}
 
 /**
 ### Parameters:
 `log_file_name`: File path for the LAMMPS log file
 `requried_thermo_run_id`: The index of the run thermo output (default = 0)
+`schema`: A dict of column names and polars data types to cast the columns to
+    (default = None, integer columns are Int64 and the others Float64)
+`strict`: Raise an error if any row of the thermo output cannot be parsed
+    (default = False, such rows are skipped)
+`mmap`: Memory map an uncompressed log file instead of streaming it, which parses
+    large logs faster (default = False, see the note below)
 Note:
 The default requried_thermo_run_id includes the MPI minimization data
 So usually what you need will start at index 1
+With `mmap=True` the log file must not be truncated while it is parsed (e.g. by a
+new LAMMPS run writing a log of the same name), which would crash the interpreter.
+Use `LogTail` to follow a running log.
+*/
+#[pyfunction]
+#[pyo3(signature = (log_file_name, requried_thermo_run_id=None, schema=None, strict=None, mmap=None))]
+fn parse(
+    log_file_name: &str,
+    requried_thermo_run_id: Option<u32>,
+    schema: Option<HashMap<String, PyDataType>>,
+    strict: Option<bool>,
+    mmap: Option<bool>,
+) -> PyResult<PyDataFrame> {
+    let report = if mmap.unwrap_or_default() {
+        // SAFETY: the caller opted into the memory map, whose docstring requires that
+        // the log file is not truncated while it is parsed
+        unsafe {
+            LogLammpsReader::parse_mapped_with_report(log_file_name.into(), requried_thermo_run_id)
+        }
+    } else {
+        LogLammpsReader::parse_with_report(log_file_name.into(), requried_thermo_run_id)
+    };
+    let parsed = report.and_then(|report| {
+        let df: DataFrame = if strict.unwrap_or_default() {
+            LogLammpsReader::reject_skipped_rows(Path::new(log_file_name), report)?
+        } else {
+            report.0
+        };
+        match schema {
+            Some(schema) => {
+                let schema: Schema = schema
+                    .into_iter()
+                    .map(|(name, dtype)| Field::new(name.into(), dtype.0))
+                    .collect();
+                LogLammpsReader::apply_schema(df, &schema)
+                    .map_err(|e| LammpsError::from_boxed(Path::new(log_file_name), e))
+            }
+            None => Ok(df),
+        }
+    });
+    match parsed {
+        Ok(df) => Ok(PyDataFrame(df)),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+`requried_thermo_run_id`: The index of the run thermo output (default = 0)
+Note:
+Returns a tuple of the thermo DataFrame and a DataFrame of the skipped rows,
+with their `line` number, the `reason` and the `text` of the line.
 */
 #[pyfunction]
 #[pyo3(signature = (log_file_name, requried_thermo_run_id=None))]
-fn parse(log_file_name: &str, requried_thermo_run_id: Option<u32>) -> PyResult<PyDataFrame> {
-    match LogLammpsReader::parse(log_file_name.into(), requried_thermo_run_id) {
+fn parse_with_report(
+    log_file_name: &str,
+    requried_thermo_run_id: Option<u32>,
+) -> PyResult<(PyDataFrame, PyDataFrame)> {
+    let parsed = LogLammpsReader::parse_with_report(log_file_name.into(), requried_thermo_run_id)
+        .and_then(|(df, skipped_rows)| {
+            let skipped_df =
+                SkippedRow::to_df(&skipped_rows).map_err(|source| LammpsError::Polars {
+                    path: log_file_name.into(),
+                    source,
+                })?;
+            Ok((df, skipped_df))
+        });
+    match parsed {
+        Ok((df, skipped_df)) => Ok((PyDataFrame(df), PyDataFrame(skipped_df))),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+`requried_thermo_run_id`: The index of the run thermo output (default = 0)
+Note:
+Returns a polars LazyFrame which parses the thermo run when it is collected.
+Only the selected columns are stored, and rows outside of a filter on `Step`
+(e.g. `pl.col('Step') > 1000`) are skipped while the log file is parsed.
+The schema is read from the header of the run: the integer thermo keywords
+(e.g. `Step`, `Atoms`) are Int64 and the other columns are Float64.
+The log file, the run and its header are checked when `scan_log` is called,
+and errors while collecting are raised as the same `LammpsReaderError` subclasses.
+The log file is streamed when the LazyFrame is collected. A filter which cannot
+be passed to the parser (e.g. from a different polars version) is applied after
+the whole run was parsed, with a `UserWarning`.
+*/
+#[pyfunction]
+#[pyo3(signature = (log_file_name, requried_thermo_run_id=None))]
+fn scan_log<'py>(
+    py: Python<'py>,
+    log_file_name: &str,
+    requried_thermo_run_id: Option<u32>,
+) -> PyResult<Bound<'py, PyAny>> {
+    let lazy_data: LazyFrame =
+        match LogLammpsReader::scan(log_file_name.into(), requried_thermo_run_id) {
+            Ok(lazy_data) => lazy_data,
+            Err(e) => {
+                return Err(reader_error("LogLammpsReader", e));
+            }
+        };
+    let schema = PyDict::new(py);
+    let collected_schema = lazy_data.clone().collect_schema().map_err(|e| {
+        reader_error(
+            "LogLammpsReader",
+            LammpsError::from_polars(Path::new(log_file_name), e),
+        )
+    })?;
+    for (name, dtype) in collected_schema.iter() {
+        schema.set_item(name.as_str(), PyDataType(dtype.clone()))?;
+    }
+    let kwargs = PyDict::new(py);
+    kwargs.set_item("schema", schema)?;
+    py.import("polars.io.plugins")?.call_method(
+        "register_io_source",
+        (LogScanSource {
+            log_file_name: log_file_name.into(),
+            lazy_data,
+        },),
+        Some(&kwargs),
+    )
+}
+
+/// Source of `scan_log`, called by polars with the selected columns and filter
+#[pyclass]
+struct LogScanSource {
+    log_file_name: PathBuf,
+    /// The LazyFrame of `LogLammpsReader::scan`, which passes the columns and the steps to the parser
+    lazy_data: LazyFrame,
+}
+
+#[pymethods]
+impl LogScanSource {
+    #[pyo3(signature = (with_columns, predicate, n_rows, _batch_size))]
+    fn __call__<'py>(
+        &self,
+        py: Python<'py>,
+        with_columns: Option<Vec<String>>,
+        predicate: Option<Bound<'py, PyAny>>,
+        n_rows: Option<usize>,
+        _batch_size: Option<usize>,
+    ) -> PyResult<Bound<'py, PyAny>> {
+        let mut lazy_data: LazyFrame = self.lazy_data.clone();
+        // A filter which cannot be read (e.g. from another polars version) is applied in python
+        let mut python_predicate: Option<Bound<'py, PyAny>> = None;
+        if let Some(predicate) = predicate {
+            match predicate.extract::<PyExpr>() {
+                Ok(expr) => lazy_data = lazy_data.filter(expr.0),
+                Err(e) => {
+                    let message: String = format!(
+                        "scan_log could not pass the filter to the parser, every row of the thermo run is parsed: {}",
+                        e
+                    );
+                    PyErr::warn(
+                        py,
+                        &py.get_type::<PyUserWarning>(),
+                        &CString::new(message)?,
+                        1,
+                    )?;
+                    python_predicate = Some(predicate);
+                }
+            }
+        }
+        if let Some(with_columns) = &with_columns {
+            let mut columns: Vec<String> = with_columns.clone();
+            // The columns used by the python filter are also needed to apply it
+            if let Some(predicate) = &python_predicate {
+                let root_names: Vec<String> = predicate
+                    .getattr("meta")?
+                    .call_method0("root_names")?
+                    .extract()?;
+                for name in root_names {
+                    if !columns.contains(&name) {
+                        columns.push(name);
+                    }
+                }
+            }
+            lazy_data = lazy_data.select(
+                columns
+                    .iter()
+                    .map(|name| col(name.as_str()))
+                    .collect::<Vec<Expr>>(),
+            );
+        }
+        if let Some(n_rows) = n_rows.filter(|_| python_predicate.is_none()) {
+            lazy_data = lazy_data.limit(n_rows as IdxSize);
+        }
+        let log_data: DataFrame = py.allow_threads(|| lazy_data.collect()).map_err(|e| {
+            reader_error(
+                "LogLammpsReader",
+                LammpsError::from_polars(&self.log_file_name, e),
+            )
+        })?;
+
+        let mut log_data: Bound<'py, PyAny> = PyDataFrame(log_data).into_pyobject(py)?;
+        if let Some(predicate) = python_predicate {
+            log_data = log_data.call_method1("filter", (predicate,))?;
+            if let Some(with_columns) = with_columns {
+                log_data = log_data.call_method1("select", (with_columns,))?;
+            }
+            if let Some(n_rows) = n_rows {
+                log_data = log_data.call_method1("head", (n_rows,))?;
+            }
+        }
+        PyList::new(py, [log_data])?
+            .try_iter()
+            .map(|iter| iter.into_any())
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+`concat`: Return a single DataFrame with a `run_id` column (default = False)
+`mmap`: Memory map an uncompressed log file instead of streaming it (default =
+    False). The log file must then not be truncated while it is parsed (see `parse`).
+Note:
+The log file is only read once. Without `concat`, this returns a list of
+DataFrames where the index is the thermo run id. With `concat`, the columns of
+all runs are merged and values missing in a run are null.
+*/
+#[pyfunction]
+#[pyo3(signature = (log_file_name, concat=None, mmap=None))]
+fn parse_all(
+    py: Python<'_>,
+    log_file_name: &str,
+    concat: Option<bool>,
+    mmap: Option<bool>,
+) -> PyResult<PyObject> {
+    let mmap: bool = mmap.unwrap_or_default();
+    let parsed = if concat.unwrap_or_default() {
+        let log_data = if mmap {
+            // SAFETY: the caller opted into the memory map, see `parse`
+            unsafe { LogLammpsReader::parse_all_concat_mapped(log_file_name.into()) }
+        } else {
+            LogLammpsReader::parse_all_concat(log_file_name.into())
+        };
+        log_data.map(|df| PyDataFrame(df).into_pyobject(py).map(|obj| obj.unbind()))
+    } else {
+        let log_data = if mmap {
+            // SAFETY: the caller opted into the memory map, see `parse`
+            unsafe { LogLammpsReader::parse_all_mapped(log_file_name.into()) }
+        } else {
+            LogLammpsReader::parse_all(log_file_name.into())
+        };
+        log_data.map(|dfs| {
+            dfs.into_iter()
+                .map(PyDataFrame)
+                .collect::<Vec<PyDataFrame>>()
+                .into_pyobject(py)
+                .map(|obj| obj.unbind())
+        })
+    };
+    match parsed {
+        Ok(obj) => obj,
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_names`: File paths of the LAMMPS log files, in the order of the simulation
+`runs`: A list with the thermo run ids to add for each log file, where None
+    adds every run with thermo data (default = None, every run of every log)
+`drop_overlap`: Drop rows which are repeated by a later run (default = False)
+Note:
+Returns a single DataFrame with `source_file` and `run_id` columns. `Step` and
+`Time` continue across `reset_timestep` commands. Restarts which go back to an
+earlier step overlap the previous log, and the overlapping rows of the earlier
+log, as well as the first row of a run repeating the last row of the previous
+run, are dropped with `drop_overlap`.
+*/
+#[pyfunction]
+#[pyo3(signature = (log_file_names, runs=None, drop_overlap=None))]
+fn stitch(
+    log_file_names: Vec<String>,
+    runs: Option<Vec<Option<Vec<u32>>>>,
+    drop_overlap: Option<bool>,
+) -> PyResult<PyDataFrame> {
+    let runs: Vec<Option<Vec<u32>>> = runs.unwrap_or_else(|| vec![None; log_file_names.len()]);
+    if runs.len() != log_file_names.len() {
+        return Err(PyErr::new::<pyo3::exceptions::PyValueError, _>(format!(
+            "LogLammpsReader error: {} run selections given for {} log files",
+            runs.len(),
+            log_file_names.len()
+        )));
+    }
+    let logs = log_file_names
+        .into_iter()
+        .map(|log_file_name| log_file_name.into())
+        .zip(runs)
+        .collect();
+    match LogLammpsReader::stitch(logs, drop_overlap.unwrap_or_default()) {
+        Ok(df) => Ok(PyDataFrame(df)),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+Note:
+Returns a DataFrame with one row for each thermo run, without loading the
+thermo data. The columns are `run_id`, `kind` (`minimize` or `run`), `columns`,
+`rows`, `first_step`, `last_step`, `start_line` and `end_line`.
+*/
+#[pyfunction]
+fn list_runs(log_file_name: &str) -> PyResult<PyDataFrame> {
+    match LogLammpsReader::list_runs_df(log_file_name.into()) {
+        Ok(df) => Ok(PyDataFrame(df)),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+Follows a LAMMPS log file while the simulation is still writing it.
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+`interval`: Seconds to wait between reads when iterating (default = 1.0)
+`timeout`: Stop iterating after this many seconds without new lines
+    (default = None, never stop)
+Note:
+`poll()` only reads the lines written since the last call and returns a list
+with a dict for every thermo run which was started, continued or finished,
+containing `run_id`, `new_run`, `finished` and the new thermo rows in `rows`.
+`run_data` has all rows of the current run read so far.
+Iterating over the object yields the same dicts as they are written.
+*/
+#[pyclass]
+struct LogTail {
+    tail: log_reader::LogTail,
+    interval: f64,
+    timeout: Option<f64>,
+    pending: VecDeque<ThermoUpdate>,
+}
+
+#[pymethods]
+impl LogTail {
+    #[new]
+    #[pyo3(signature = (log_file_name, interval=None, timeout=None))]
+    fn new(log_file_name: &str, interval: Option<f64>, timeout: Option<f64>) -> Self {
+        LogTail {
+            tail: LogLammpsReader::tail(log_file_name.into()),
+            interval: interval.unwrap_or(1.0),
+            timeout,
+            pending: VecDeque::new(),
+        }
+    }
+
+    fn poll<'py>(&mut self, py: Python<'py>) -> PyResult<Vec<Bound<'py, PyDict>>> {
+        let mut updates: Vec<ThermoUpdate> = self.pending.drain(..).collect();
+        updates.extend(self.read_updates()?);
+        updates
+            .into_iter()
+            .map(|update| thermo_update_dict(py, update))
+            .collect()
+    }
+
+    #[getter]
+    fn run_id(&self) -> Option<u32> {
+        self.tail.run_id()
+    }
+
+    #[getter]
+    fn run_data(&mut self) -> PyDataFrame {
+        PyDataFrame(self.tail.run_data().clone())
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__<'py>(&mut self, py: Python<'py>) -> PyResult<Option<Bound<'py, PyDict>>> {
+        let mut waited: f64 = 0.0;
+        loop {
+            if let Some(update) = self.pending.pop_front() {
+                return thermo_update_dict(py, update).map(Some);
+            }
+            let updates: Vec<ThermoUpdate> = self.read_updates()?;
+            if !updates.is_empty() {
+                self.pending.extend(updates);
+                continue;
+            }
+            if self.timeout.is_some_and(|timeout| waited >= timeout) {
+                return Ok(None);
+            }
+            let interval: f64 = self.interval;
+            py.allow_threads(|| std::thread::sleep(Duration::from_secs_f64(interval)));
+            py.check_signals()?;
+            waited += interval;
+        }
+    }
+}
+
+impl LogTail {
+    fn read_updates(&mut self) -> PyResult<Vec<ThermoUpdate>> {
+        match self.tail.poll() {
+            Ok(updates) => Ok(updates),
+            Err(e) => Err(reader_error("LogLammpsReader", e)),
+        }
+    }
+}
+
+fn thermo_update_dict(py: Python<'_>, update: ThermoUpdate) -> PyResult<Bound<'_, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("run_id", update.run_id)?;
+    dict.set_item("new_run", update.new_run)?;
+    dict.set_item("finished", update.finished)?;
+    dict.set_item("rows", PyDataFrame(update.rows))?;
+    Ok(dict)
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+Note:
+Returns a list with a dict for every finished run containing `run_id`,
+`loop_time`, `procs`, `steps`, `atoms`, `ns_per_day`, `hours_per_ns`,
+`timesteps_per_s`, `katom_step_per_s`, `cpu_use`, `mpi_tasks`, `omp_threads`
+and the MPI task timing breakdown as a DataFrame in `mpi_timing`.
+*/
+#[pyfunction]
+fn run_performance<'py>(py: Python<'py>, log_file_name: &str) -> PyResult<Vec<Bound<'py, PyDict>>> {
+    let performance = match LogLammpsReader::run_performance(log_file_name.into()) {
+        Ok(performance) => performance,
+        Err(e) => {
+            return Err(reader_error("LogLammpsReader", e));
+        }
+    };
+    performance
+        .into_iter()
+        .map(|run| {
+            let dict = PyDict::new(py);
+            dict.set_item("run_id", run.run_id)?;
+            dict.set_item("loop_time", run.loop_time)?;
+            dict.set_item("procs", run.procs)?;
+            dict.set_item("steps", run.steps)?;
+            dict.set_item("atoms", run.atoms)?;
+            dict.set_item("ns_per_day", run.ns_per_day)?;
+            dict.set_item("hours_per_ns", run.hours_per_ns)?;
+            dict.set_item("timesteps_per_s", run.timesteps_per_s)?;
+            dict.set_item("katom_step_per_s", run.katom_step_per_s)?;
+            dict.set_item("cpu_use", run.cpu_use)?;
+            dict.set_item("mpi_tasks", run.mpi_tasks)?;
+            dict.set_item("omp_threads", run.omp_threads)?;
+            dict.set_item("mpi_timing", PyDataFrame(run.mpi_timing))?;
+            Ok(dict)
+        })
+        .collect()
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+Note:
+Returns a DataFrame with one row for each finished run containing the
+`Nlocal`, `Nghost` and `Neighs` distributions (`_ave`, `_max`, `_min` and
+`_histogram` columns), `total_neighbors`, `ave_neighs_per_atom`,
+`ave_special_neighs_per_atom`, `neighbor_list_builds` and `dangerous_builds`.
+*/
+#[pyfunction]
+fn neighbor_stats(log_file_name: &str) -> PyResult<PyDataFrame> {
+    match LogLammpsReader::neighbor_stats_df(log_file_name.into()) {
         Ok(df) => Ok(PyDataFrame(df)),
-        Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-            "LogLammpsReader error: {}",
-            e
-        ))),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+Note:
+Returns a DataFrame with one row for each finished `minimize` run containing
+its `run_id`, `stopping_criterion`, the initial, next-to-last and final energy,
+force two-norm, max force component, line search alpha, max atom move,
+`iterations` and `force_evaluations`.
+*/
+#[pyfunction]
+fn minimization_stats(log_file_name: &str) -> PyResult<PyDataFrame> {
+    match LogLammpsReader::minimization_stats_df(log_file_name.into()) {
+        Ok(df) => Ok(PyDataFrame(df)),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+Note:
+Returns a dict with the LAMMPS `version` and the `units`, `atom_style`,
+`dimension`, `boundary`, `timestep`, `atoms`, `box`, `processor_grid`,
+`mpi_tasks` and `omp_threads` at the end of the log file. `runs` is a list with
+a dict of the same settings for each thermo run, along with its `run_id`.
+`box` is a dict with `triclinic`, `lo`, `hi` and the `tilt` factors (xy, xz, yz).
+Settings which are not in the log file are None.
+*/
+#[pyfunction]
+fn log_metadata<'py>(py: Python<'py>, log_file_name: &str) -> PyResult<Bound<'py, PyDict>> {
+    let metadata = match LogLammpsReader::log_metadata(log_file_name.into()) {
+        Ok(metadata) => metadata,
+        Err(e) => {
+            return Err(reader_error("LogLammpsReader", e));
+        }
+    };
+    let dict = simulation_setup_dict(py, &metadata.setup)?;
+    dict.set_item("version", metadata.version)?;
+    let runs = metadata
+        .runs
+        .iter()
+        .map(|run| {
+            let run_dict = simulation_setup_dict(py, &run.setup)?;
+            run_dict.set_item("run_id", run.run_id)?;
+            Ok(run_dict)
+        })
+        .collect::<PyResult<Vec<Bound<'py, PyDict>>>>()?;
+    dict.set_item("runs", runs)?;
+    Ok(dict)
+}
+
+fn simulation_setup_dict<'py>(
+    py: Python<'py>,
+    setup: &SimulationSetup,
+) -> PyResult<Bound<'py, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("units", &setup.units)?;
+    dict.set_item("atom_style", &setup.atom_style)?;
+    dict.set_item("dimension", setup.dimension)?;
+    dict.set_item("boundary", &setup.boundary)?;
+    dict.set_item("timestep", setup.timestep)?;
+    dict.set_item("atoms", setup.atoms)?;
+    let simulation_box = match &setup.simulation_box {
+        Some(simulation_box) => {
+            let box_dict = PyDict::new(py);
+            box_dict.set_item("triclinic", simulation_box.triclinic)?;
+            box_dict.set_item("lo", simulation_box.lo)?;
+            box_dict.set_item("hi", simulation_box.hi)?;
+            box_dict.set_item("tilt", simulation_box.tilt)?;
+            Some(box_dict)
+        }
+        None => None,
+    };
+    dict.set_item("box", simulation_box)?;
+    dict.set_item("processor_grid", setup.processor_grid)?;
+    dict.set_item("mpi_tasks", setup.mpi_tasks)?;
+    dict.set_item("omp_threads", setup.omp_threads)?;
+    Ok(dict)
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+Note:
+Returns a DataFrame with one row for each `WARNING` or `ERROR` message in the
+log file containing its `line` number, `level`, the `run_id` of the thermo
+output it was printed in (null outside of thermo output), the `last_step`
+printed before it, the `message` and the source `location` reported by LAMMPS.
+*/
+#[pyfunction]
+fn diagnostics(log_file_name: &str) -> PyResult<PyDataFrame> {
+    match LogLammpsReader::diagnostics_df(log_file_name.into()) {
+        Ok(df) => Ok(PyDataFrame(df)),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
     }
 }
 
@@ -34,26 +690,57 @@ Parses a LAMMPS dump file and returns a BTreeMap/dict of timesteps and polars Da
 
 # Arguments
 * `dump_file_name` - A string slice representing the name of the LAMMPS dump file to be parsed.
+* `start`, `stop`, `step` - Keep the frames of `range(start, stop, step)` by frame index
+  (default = every frame), reading stops at `stop`.
+* `min_timestep`, `max_timestep` - Keep the frames whose timestep is in this inclusive range.
+* `timesteps` - Keep the frames of these timesteps.
+
+The atom lines of the frames which are not kept are not parsed.
 
 # Returns
 * `dict{int,polars.DataFrame}` - A Python result containing a BTreeMap where the keys
   are timesteps (int) and the values are polars DataFrame objects, or a Python exception if an error occurs.
 
 # Errors
- Returns a `PyException` if the `DumpLammpsReader::parse` function fails.
+ Returns a `LammpsReaderError` subclass if the `DumpLammpsReader::parse` function fails,
+ e.g. `TruncatedFrameError` with the `path` and `line` of a frame which ended too soon.
 */
 #[pyfunction]
-#[pyo3(signature = (dump_file_name))]
-fn parse_dump(dump_file_name: &str) -> PyResult<BTreeMap<u64, PyDataFrame>> {
-    match DumpLammpsReader::parse(dump_file_name.into()) {
+#[pyo3(signature = (
+    dump_file_name,
+    start=None,
+    stop=None,
+    step=None,
+    min_timestep=None,
+    max_timestep=None,
+    timesteps=None
+))]
+fn parse_dump(
+    dump_file_name: &str,
+    start: Option<usize>,
+    stop: Option<usize>,
+    step: Option<usize>,
+    min_timestep: Option<u64>,
+    max_timestep: Option<u64>,
+    timesteps: Option<BTreeSet<u64>>,
+) -> PyResult<BTreeMap<u64, PyDataFrame>> {
+    if step == Some(0) {
+        return Err(PyValueError::new_err("step must not be zero"));
+    }
+    let selection = DumpSelection {
+        start,
+        stop,
+        step,
+        min_timestep,
+        max_timestep,
+        timesteps,
+    };
+    match DumpLammpsReader::parse(dump_file_name.into(), selection) {
         Ok(df_map) => Ok(df_map
             .into_iter()
             .map(|(timestep, df)| (timestep, PyDataFrame(df)))
             .collect()),
-        Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-            "DumpLammpsReader error: {}",
-            e
-        ))),
+        Err(e) => Err(reader_error("DumpLammpsReader", e)),
     }
 }
 
@@ -62,10 +749,192 @@ fn parse_dump(dump_file_name: &str) -> PyResult<BTreeMap<u64, PyDataFrame>> {
 fn parse_dump_state(dump_file_name: &str) -> PyResult<PyDataFrame> {
     match DumpLammpsReader::parse_state(dump_file_name.into()) {
         Ok(df) => Ok(PyDataFrame(df)),
-        Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-            "DumpLammpsReader error: {}",
-            e
-        ))),
+        Err(e) => Err(reader_error("DumpLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`dump_file_name`: File path for the LAMMPS dump file
+Note:
+Returns a dict of timesteps and the 3x3 cell matrix of each frame, whose rows
+are the edge vectors `a = (lx, 0, 0)`, `b = (xy, ly, 0)` and `c = (xz, yz, lz)`.
+The atoms are not parsed.
+*/
+#[pyfunction]
+#[pyo3(signature = (dump_file_name))]
+fn parse_dump_cells(dump_file_name: &str) -> PyResult<BTreeMap<u64, [[f64; 3]; 3]>> {
+    match DumpLammpsReader::parse_boxes(dump_file_name.into()) {
+        Ok(boxes) => Ok(boxes
+            .into_iter()
+            .map(|(timestep, dump_box)| (timestep, dump_box.cell_matrix()))
+            .collect()),
+        Err(e) => Err(reader_error("DumpLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`dump_file_name`: File path for the LAMMPS dump file
+Note:
+Returns an iterator which reads one frame at a time and yields a tuple of the
+timestep (int) and the atoms (polars DataFrame), so that dump files larger than
+the memory can be read. `cell` is the 3x3 cell matrix of the last frame.
+*/
+#[pyfunction]
+#[pyo3(signature = (dump_file_name))]
+fn iter_dump(dump_file_name: &str) -> PyResult<DumpIterator> {
+    match DumpLammpsReader::iter_frames(dump_file_name.into()) {
+        Ok(frames) => Ok(DumpIterator {
+            frames: Mutex::new(frames),
+            cell: None,
+        }),
+        Err(e) => Err(reader_error("DumpLammpsReader", e)),
+    }
+}
+
+/// Iterator of `iter_dump`
+#[pyclass]
+struct DumpIterator {
+    // The file reader is not Sync, which python classes must be
+    frames: Mutex<DumpFrameIter>,
+    cell: Option<[[f64; 3]; 3]>,
+}
+
+#[pymethods]
+impl DumpIterator {
+    #[getter]
+    fn cell(&self) -> Option<[[f64; 3]; 3]> {
+        self.cell
+    }
+
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(&mut self, py: Python<'_>) -> PyResult<Option<(u64, PyDataFrame)>> {
+        let frames: &mut DumpFrameIter = self.frames.get_mut().map_err(|_| {
+            PyErr::new::<LammpsReaderError, _>(
+                "DumpLammpsReader error: the dump file reader panicked",
+            )
+        })?;
+        match py.allow_threads(|| frames.next()) {
+            Some(Ok((timestep, dump_box, atoms))) => {
+                self.cell = Some(dump_box.cell_matrix());
+                Ok(Some((timestep, PyDataFrame(atoms))))
+            }
+            Some(Err(e)) => Err(reader_error("DumpLammpsReader", e)),
+            None => Ok(None),
+        }
+    }
+}
+
+/**
+### Parameters:
+`dump_file_name`: File path for the uncompressed LAMMPS dump file
+`save_index`: Load the frame index from `<dump_file_name>.idx`, or save it there
+    if the directory is writable (default = False)
+Note:
+Finds the byte offset of every frame in a single scan of the file, so that frames
+can be read without reading the frames before them. `dump[i]` returns a tuple of
+the timestep (int) and the atoms (polars DataFrame), `dump[::10]` a list of them.
+*/
+#[pyclass]
+struct DumpFile {
+    index: DumpFrameIndex,
+}
+
+#[pymethods]
+impl DumpFile {
+    #[new]
+    #[pyo3(signature = (dump_file_name, save_index=None))]
+    fn new(py: Python<'_>, dump_file_name: &str, save_index: Option<bool>) -> PyResult<DumpFile> {
+        let save_index: bool = save_index.unwrap_or_default();
+        match py.allow_threads(|| DumpLammpsReader::index(dump_file_name.into(), save_index)) {
+            Ok(index) => Ok(DumpFile { index }),
+            Err(e) => Err(reader_error("DumpLammpsReader", e)),
+        }
+    }
+
+    fn __len__(&self) -> usize {
+        self.index.len()
+    }
+
+    /// Timesteps of the frames in the order of the dump file
+    #[getter]
+    fn timesteps(&self) -> Vec<u64> {
+        self.index.timesteps()
+    }
+
+    fn __getitem__(&self, py: Python<'_>, key: &Bound<'_, PyAny>) -> PyResult<PyObject> {
+        if let Ok(slice) = key.downcast::<PySlice>() {
+            let indices = slice.indices(self.index.len() as isize)?;
+            let frame_ids: Vec<usize> = (0..indices.slicelength)
+                .map(|i| (indices.start + i as isize * indices.step) as usize)
+                .collect();
+            let frames = py
+                .allow_threads(|| self.index.read_frames(&frame_ids))
+                .map_err(|e| reader_error("DumpLammpsReader", e))?;
+            let frames: Vec<(u64, PyDataFrame)> = frames
+                .into_iter()
+                .map(|(timestep, _, atoms)| (timestep, PyDataFrame(atoms)))
+                .collect();
+            return Ok(frames.into_pyobject(py)?.into_any().unbind());
+        }
+        let frame_id: usize = self.frame_id(key.extract()?)?;
+        let (timestep, _, atoms) = py
+            .allow_threads(|| self.index.read_frame(frame_id))
+            .map_err(|e| reader_error("DumpLammpsReader", e))?;
+        Ok((timestep, PyDataFrame(atoms))
+            .into_pyobject(py)?
+            .into_any()
+            .unbind())
+    }
+
+    /**
+    ### Parameters:
+    `timestep`: Timestep of the frame
+    Note:
+    Returns the atoms (polars DataFrame) of the first frame of the timestep
+    */
+    fn read_timestep(&self, py: Python<'_>, timestep: u64) -> PyResult<PyDataFrame> {
+        match py.allow_threads(|| self.index.read_timestep(timestep)) {
+            Ok((_, _, atoms)) => Ok(PyDataFrame(atoms)),
+            Err(e) => Err(reader_error("DumpLammpsReader", e)),
+        }
+    }
+
+    /**
+    ### Parameters:
+    `frame_id`: Index of the frame, starting at 0 (negative counts from the end)
+    Note:
+    Returns the 3x3 cell matrix of the frame, without reading its atoms
+    */
+    fn cell(&self, py: Python<'_>, frame_id: isize) -> PyResult<[[f64; 3]; 3]> {
+        let frame_id: usize = self.frame_id(frame_id)?;
+        match py.allow_threads(|| self.index.read_box(frame_id)) {
+            Ok((_, dump_box)) => Ok(dump_box.cell_matrix()),
+            Err(e) => Err(reader_error("DumpLammpsReader", e)),
+        }
+    }
+}
+
+impl DumpFile {
+    /// Converts a python index, which counts from the end if negative, into a frame index
+    fn frame_id(&self, frame_id: isize) -> PyResult<usize> {
+        let len: isize = self.index.len() as isize;
+        let frame_id: isize = if frame_id < 0 {
+            frame_id + len
+        } else {
+            frame_id
+        };
+        if !(0..len).contains(&frame_id) {
+            return Err(PyIndexError::new_err(format!(
+                "Frame index out of range, the dump file has {} frames",
+                len
+            )));
+        }
+        Ok(frame_id as usize)
     }
 }
 
@@ -80,10 +949,49 @@ fn new(log_file_name: &str, requried_thermo_run_id: Option<u32>) -> PyResult<PyD
     );
     match LogLammpsReader::parse(log_file_name.into(), requried_thermo_run_id) {
         Ok(df) => Ok(PyDataFrame(df)),
-        Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-            "LogLammpsReader error: {}",
-            e
-        ))),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+`patterns`: A list of prefixes of the lines, or of regular expressions
+`regex`: Match the patterns as regular expressions (default = False), which
+    may have named capture groups, e.g. `fix (?P<fix_id>\S+) (?P<group>\S+)`
+Note:
+Returns a DataFrame with one row for each matched line, containing its `line`
+number, the `run_id` of the thermo run (lines between runs belong to the next
+run), the index of the first `pattern` which matched, the trimmed `text` and a
+column for every named capture group.
+*/
+#[pyfunction]
+#[pyo3(signature = (log_file_name, patterns, regex=None))]
+fn log_grep(
+    log_file_name: &str,
+    patterns: Vec<String>,
+    regex: Option<bool>,
+) -> PyResult<PyDataFrame> {
+    match LogLammpsReader::log_grep(log_file_name.into(), &patterns, regex.unwrap_or_default()) {
+        Ok(df) => Ok(PyDataFrame(df)),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
+    }
+}
+
+/**
+### Parameters:
+`log_file_name`: File path for the LAMMPS log file
+Note:
+Returns a DataFrame with one row for each command of the input script echoed in
+the log file, containing its `line` number, the `run_id` of the thermo run which
+follows it, the `raw` command and the `substituted` command after the variables
+were replaced by their values.
+*/
+#[pyfunction]
+fn input_commands(log_file_name: &str) -> PyResult<PyDataFrame> {
+    match LogLammpsReader::input_commands_df(log_file_name.into()) {
+        Ok(df) => Ok(PyDataFrame(df)),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
     }
 }
 
@@ -99,10 +1007,7 @@ This returns a list of strings that satisfy the above key.
 fn log_starts_with(log_file_name: &str, prefix_key: &str) -> PyResult<Vec<String>> {
     match LogLammpsReader::log_starts_with(log_file_name.into(), prefix_key) {
         Ok(matched_lines) => Ok(matched_lines),
-        Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-            "LogLammpsReader error: {}",
-            e
-        ))),
+        Err(e) => Err(reader_error("LogLammpsReader", e)),
     }
 }
 
@@ -117,10 +1022,7 @@ fn mean_square_displacement(
         unwrap_trajectory.unwrap_or_default(),
     ) {
         Ok(msd) => Ok(msd),
-        Err(e) => Err(PyErr::new::<pyo3::exceptions::PyException, _>(format!(
-            "AnalyzeLammps error: {}",
-            e
-        ))),
+        Err(e) => Err(reader_error("AnalyzeLammps", e)),
     }
 }
 
@@ -135,10 +1037,50 @@ file data into a DataFrame. */
 #[pymodule]
 fn log_lammps_reader(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(new, m)?)?;
+    m.add("LammpsReaderError", m.py().get_type::<LammpsReaderError>())?;
+    m.add(
+        "LammpsFileNotFoundError",
+        m.py().get_type::<LammpsFileNotFoundError>(),
+    )?;
+    m.add(
+        "UnsupportedFormatError",
+        m.py().get_type::<UnsupportedFormatError>(),
+    )?;
+    m.add(
+        "MalformedHeaderError",
+        m.py().get_type::<MalformedHeaderError>(),
+    )?;
+    m.add(
+        "TruncatedFrameError",
+        m.py().get_type::<TruncatedFrameError>(),
+    )?;
+    m.add("RunNotFoundError", m.py().get_type::<RunNotFoundError>())?;
+    m.add(
+        "FrameNotFoundError",
+        m.py().get_type::<FrameNotFoundError>(),
+    )?;
+    m.add("InvalidDataError", m.py().get_type::<InvalidDataError>())?;
     m.add_function(wrap_pyfunction!(parse, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_with_report, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_all, m)?)?;
+    m.add_function(wrap_pyfunction!(scan_log, m)?)?;
+    m.add_function(wrap_pyfunction!(stitch, m)?)?;
+    m.add_class::<LogTail>()?;
+    m.add_function(wrap_pyfunction!(list_runs, m)?)?;
+    m.add_function(wrap_pyfunction!(run_performance, m)?)?;
+    m.add_function(wrap_pyfunction!(neighbor_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(minimization_stats, m)?)?;
+    m.add_function(wrap_pyfunction!(diagnostics, m)?)?;
+    m.add_function(wrap_pyfunction!(log_metadata, m)?)?;
     m.add_function(wrap_pyfunction!(parse_dump, m)?)?;
     m.add_function(wrap_pyfunction!(parse_dump_state, m)?)?;
+    m.add_function(wrap_pyfunction!(parse_dump_cells, m)?)?;
+    m.add_function(wrap_pyfunction!(iter_dump, m)?)?;
+    m.add_class::<DumpIterator>()?;
+    m.add_class::<DumpFile>()?;
     m.add_function(wrap_pyfunction!(log_starts_with, m)?)?;
+    m.add_function(wrap_pyfunction!(log_grep, m)?)?;
+    m.add_function(wrap_pyfunction!(input_commands, m)?)?;
     let analyze = PyModule::new(m.py(), "analyze")?;
     analyze.add_function(wrap_pyfunction!(mean_square_displacement, &analyze)?)?;
     m.add_submodule(&analyze)?;
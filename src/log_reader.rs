@@ -1,8 +1,19 @@
+use crate::compression::{Compression, decompressed_reader};
+use crate::error::LammpsError;
+use crate::input_script::{InputCommand, InputScript};
+use crate::metadata::LogMetadata;
+use crate::run_stats::{MinimizationStats, NeighborStats, RunPerformance};
+use crate::timeline::{TimelineSegment, stitch_segments};
+use memchr::{memchr, memchr_iter};
+use memmap2::Mmap;
 use polars::prelude::*;
 use rayon::prelude::*;
+use regex::Regex;
+use std::borrow::Cow;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
-use std::path::PathBuf;
+use std::io::{BufRead, Read, Seek, SeekFrom};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
 
 /** This Rust code uses the Polars library to parse log files,
 particularly from LAMMPS simulations. The goal is to read
@@ -10,11 +21,947 @@ specific data blocks from the log file and convert them
 into a DataFrame format for further analysis. */
 pub struct LogLammpsReader {
     log_file_name: PathBuf,
+    /// Memory map uncompressed log files instead of streaming them (see `parse_mapped_with_report`)
+    mmap: bool,
 }
 
 const MPI_FLAG: &str = "Per MPI rank memory allocation";
 const ERROR_FLAGS: [&str; 2] = ["Loop time", "ERROR"];
-const UNSUPPORTED_THERMO_STYLES: [&str; 2] = ["thermo_style multi", "thermo_style yaml"];
+const WARNING_FLAG: &str = "WARNING";
+const MULTI_STEP_FLAG: &str = "-- Step";
+const YAML_START_FLAG: &str = "---";
+const YAML_KEYWORDS_FLAG: &str = "keywords:";
+const YAML_ROW_FLAG: &str = "- [";
+const STEP_COLUMN: &str = "Step";
+const RESET_TIMESTEP_FLAG: &str = "reset_timestep";
+/// Columns printed by LAMMPS for the `thermo_style` keywords, see the `thermo_style` doc
+const THERMO_KEYWORDS: [&str; 67] = [
+    "Step",
+    "Elapsed",
+    "Elaplong",
+    "Dt",
+    "Time",
+    "CPU",
+    "T/CPU",
+    "S/CPU",
+    "CPULeft",
+    "Part",
+    "TimeoutLeft",
+    "Atoms",
+    "Temp",
+    "Press",
+    "PotEng",
+    "KinEng",
+    "TotEng",
+    "Enthalpy",
+    "E_vdwl",
+    "E_coul",
+    "E_pair",
+    "E_bond",
+    "E_angle",
+    "E_dihed",
+    "E_impro",
+    "E_mol",
+    "E_long",
+    "E_tail",
+    "Ecouple",
+    "Econserve",
+    "Volume",
+    "Density",
+    "Lx",
+    "Ly",
+    "Lz",
+    "Xlo",
+    "Xhi",
+    "Ylo",
+    "Yhi",
+    "Zlo",
+    "Zhi",
+    "Xy",
+    "Xz",
+    "Yz",
+    "Xlat",
+    "Ylat",
+    "Zlat",
+    "Bonds",
+    "Angles",
+    "Diheds",
+    "Impros",
+    "Pxx",
+    "Pyy",
+    "Pzz",
+    "Pxy",
+    "Pxz",
+    "Pyz",
+    "Fmax",
+    "Fnorm",
+    "Nbuild",
+    "Ndanger",
+    "Cella",
+    "Cellb",
+    "Cellc",
+    "CellAlpha",
+    "CellBeta",
+    "CellGamma",
+];
+/// Thermo keywords whose values are integers, the other columns of `scan` are floats
+const THERMO_INTEGER_KEYWORDS: [&str; 11] = [
+    "Step", "Elapsed", "Elaplong", "Part", "Atoms", "Bonds", "Angles", "Diheds", "Impros",
+    "Nbuild", "Ndanger",
+];
+/// Prefixes of the columns of computes, fixes, variables and custom per-atom properties
+const THERMO_REFERENCE_PREFIXES: [&str; 7] = ["c_", "f_", "v_", "d_", "i_", "d2_", "i2_"];
+/// Smallest chunk of thermo data which is parsed by a single thread
+const MIN_CHUNK_SIZE: usize = 1 << 20;
+
+/// Section of the log file that a line belongs to
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum LogSection {
+    /// Input commands and setup output between thermo runs
+    Input,
+    /// First line of a thermo run, which is the header for `thermo_style one/custom`
+    Header,
+    /// Thermo output of a run
+    Thermo,
+    /// The `Loop time` or `ERROR` line which ends a thermo run
+    End,
+}
+
+/// A line of the log file along with its position relative to the thermo runs
+struct LogLine<'a> {
+    /// Line number in the log file, starting at 1
+    number: usize,
+    /// The thermo run of the line. Input lines belong to the next thermo run.
+    thermo_run_id: u32,
+    section: LogSection,
+    text: &'a str,
+}
+
+/** Keeps track of the thermo run and the section of the log while it is read line by line.
+
+A thermo run starts after the `Per MPI rank memory allocation` line and ends at
+`Loop time` or `ERROR`. Its header is the first line after that which is a thermo
+header, the lines in between are input lines. */
+#[derive(Default)]
+struct LogScanner {
+    thermo_run_id: u32,
+    line_number: usize,
+    data_flag: bool,
+    header_flag: bool,
+    run_ended: bool,
+}
+
+impl LogScanner {
+    /// Returns the next line of the log with the thermo run and section it belongs to
+    fn scan<'a>(&mut self, line: &'a str) -> LogLine<'a> {
+        if self.run_ended {
+            self.thermo_run_id += 1;
+            self.run_ended = false;
+        }
+        self.line_number += 1;
+
+        let section: LogSection = if !self.data_flag {
+            if line.starts_with(MPI_FLAG) {
+                self.data_flag = true;
+            }
+            LogSection::Input
+        } else if !self.header_flag && is_thermo_header(line) {
+            self.header_flag = true;
+            LogSection::Header
+        } else if ERROR_FLAGS.iter().any(|&flag| line.starts_with(flag)) {
+            self.data_flag = false;
+            self.header_flag = false;
+            self.run_ended = true;
+            LogSection::End
+        } else if !self.header_flag {
+            LogSection::Input
+        } else {
+            LogSection::Thermo
+        };
+
+        LogLine {
+            number: self.line_number,
+            thermo_run_id: self.thermo_run_id,
+            section,
+            text: line,
+        }
+    }
+}
+
+/// The lines of a single thermo run
+struct ThermoRunLines {
+    /// Line number of the first line of the run, the data lines follow it
+    header_line: usize,
+    log_header_str: String,
+    /// The lines after the header, each followed by a newline. Only filled when the
+    /// log is streamed, a memory-mapped log gives their byte range in `data` instead.
+    raw_log_data: String,
+    data: Range<usize>,
+    /// False when the run ended before a thermo header, then the first line of the
+    /// run which is not a header is kept in place of the header
+    header_found: bool,
+}
+
+impl ThermoRunLines {
+    fn new(header_line: usize, log_header_str: &str, position: usize, header_found: bool) -> Self {
+        ThermoRunLines {
+            header_line,
+            log_header_str: log_header_str.to_string(),
+            raw_log_data: String::new(),
+            data: position..position,
+            header_found,
+        }
+    }
+
+    /// Adds a line after the header of a run of a streamed log
+    fn push_line(&mut self, line: &str) {
+        self.raw_log_data.push_str(line);
+        self.raw_log_data.push('\n');
+    }
+
+    /// Returns the lines after the header, from the memory-mapped log if there is one
+    fn log_data<'a>(&'a self, log_map: Option<&'a [u8]>) -> &'a [u8] {
+        match log_map {
+            Some(log_map) => &log_map[self.data.clone()],
+            None => self.raw_log_data.as_bytes(),
+        }
+    }
+}
+
+/// A number in a row of thermo output
+#[derive(Clone, Copy)]
+enum ThermoValue {
+    Int(i64),
+    Float(f64),
+}
+
+impl ThermoValue {
+    fn parse(value: &str) -> Option<ThermoValue> {
+        match value.parse::<i64>() {
+            Ok(number) => Some(ThermoValue::Int(number)),
+            Err(_) => parse_thermo_value(value).map(ThermoValue::Float),
+        }
+    }
+}
+
+/// The values of a column in a chunk, i64 until a value which is not an integer is found
+#[derive(Clone)]
+enum ColumnBuffer {
+    Int(Vec<i64>),
+    Float(Vec<f64>),
+}
+
+impl ColumnBuffer {
+    fn push(&mut self, value: ThermoValue) {
+        match (&mut *self, value) {
+            (ColumnBuffer::Int(values), ThermoValue::Int(number)) => values.push(number),
+            (ColumnBuffer::Float(values), ThermoValue::Int(number)) => values.push(number as f64),
+            (ColumnBuffer::Float(values), ThermoValue::Float(number)) => values.push(number),
+            (ColumnBuffer::Int(values), ThermoValue::Float(number)) => {
+                let mut float_values: Vec<f64> = values.iter().map(|&v| v as f64).collect();
+                float_values.push(number);
+                *self = ColumnBuffer::Float(float_values);
+            }
+        }
+    }
+
+    /** Joins the buffers of every chunk into a column without copying them.
+
+    The column is i64 if every buffer is i64, otherwise f64. */
+    fn concat(name: &str, buffers: Vec<ColumnBuffer>) -> PolarsResult<Column> {
+        let is_float: bool = buffers
+            .iter()
+            .any(|buffer| matches!(buffer, ColumnBuffer::Float(_)));
+        let mut column: Series = match is_float {
+            true => Float64Chunked::from_vec(name.into(), Vec::new()).into_series(),
+            false => Int64Chunked::from_vec(name.into(), Vec::new()).into_series(),
+        };
+        for buffer in buffers {
+            let chunk: Series = match buffer {
+                ColumnBuffer::Int(values) if is_float => Float64Chunked::from_vec(
+                    name.into(),
+                    values.into_iter().map(|v| v as f64).collect(),
+                )
+                .into_series(),
+                ColumnBuffer::Int(values) => {
+                    Int64Chunked::from_vec(name.into(), values).into_series()
+                }
+                ColumnBuffer::Float(values) => {
+                    Float64Chunked::from_vec(name.into(), values).into_series()
+                }
+            };
+            column.append_owned(chunk)?;
+        }
+        Ok(column.into())
+    }
+}
+
+/** Range of steps which are kept while parsing a thermo run, both ends included.
+
+It is taken from a filter on the `Step` column, and may keep more rows than the
+filter does (e.g. for `Step > 100` it keeps step 100), so the filter still has to
+be applied to the parsed rows. */
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct StepRange {
+    pub start: Option<i64>,
+    pub stop: Option<i64>,
+}
+
+impl StepRange {
+    /** Finds the steps kept by a filter expression.
+
+    Comparisons of the `Step` column with a number, combined with `&`, are
+    understood. Any other filter keeps every step. */
+    pub fn from_predicate(predicate: &Expr) -> StepRange {
+        let Expr::BinaryExpr { left, op, right } = predicate else {
+            return StepRange::default();
+        };
+        if matches!(op, Operator::And | Operator::LogicalAnd) {
+            return StepRange::from_predicate(left).intersect(StepRange::from_predicate(right));
+        }
+        match (left.as_ref(), right.as_ref()) {
+            (Expr::Column(name), Expr::Literal(value)) if name.as_str() == STEP_COLUMN => {
+                match value.to_any_value() {
+                    Some(value) if value.dtype().is_integer() => value
+                        .extract::<i64>()
+                        .map_or(StepRange::default(), |value| {
+                            StepRange::default().with_comparison(*op, value)
+                        }),
+                    Some(value) if value.dtype().is_float() => value
+                        .extract::<f64>()
+                        .map_or(StepRange::default(), |value| {
+                            StepRange::default().with_float_comparison(*op, value)
+                        }),
+                    _ => StepRange::default(),
+                }
+            }
+            (Expr::Literal(_), Expr::Column(_)) => {
+                let flipped_op: Operator = match op {
+                    Operator::Lt => Operator::Gt,
+                    Operator::LtEq => Operator::GtEq,
+                    Operator::Gt => Operator::Lt,
+                    Operator::GtEq => Operator::LtEq,
+                    op => *op,
+                };
+                StepRange::from_predicate(&Expr::BinaryExpr {
+                    left: right.clone(),
+                    op: flipped_op,
+                    right: left.clone(),
+                })
+            }
+            _ => StepRange::default(),
+        }
+    }
+
+    /// Narrows the range with the comparison `Step <op> value`
+    pub fn with_comparison(self, op: Operator, value: i64) -> StepRange {
+        let bound = StepRange {
+            start: matches!(op, Operator::Gt | Operator::GtEq | Operator::Eq).then_some(value),
+            stop: matches!(op, Operator::Lt | Operator::LtEq | Operator::Eq).then_some(value),
+        };
+        self.intersect(bound)
+    }
+
+    /** Narrows the range with the comparison `Step <op> value` for a float value.
+
+    Steps are integers, so the range starts at the next integer and stops at the
+    previous one, e.g. `Step == 10.5` keeps no step. */
+    pub fn with_float_comparison(self, op: Operator, value: f64) -> StepRange {
+        if !value.is_finite() {
+            return self;
+        }
+        let bound = StepRange {
+            start: matches!(op, Operator::Gt | Operator::GtEq | Operator::Eq)
+                .then(|| value.ceil() as i64),
+            stop: matches!(op, Operator::Lt | Operator::LtEq | Operator::Eq)
+                .then(|| value.floor() as i64),
+        };
+        self.intersect(bound)
+    }
+
+    /// Returns the steps which are in both ranges
+    pub fn intersect(self, other: StepRange) -> StepRange {
+        let max = |a: Option<i64>, b: Option<i64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.max(b)),
+            _ => a.or(b),
+        };
+        let min = |a: Option<i64>, b: Option<i64>| match (a, b) {
+            (Some(a), Some(b)) => Some(a.min(b)),
+            _ => a.or(b),
+        };
+        StepRange {
+            start: max(self.start, other.start),
+            stop: min(self.stop, other.stop),
+        }
+    }
+
+    pub fn contains(&self, step: i64) -> bool {
+        self.start.is_none_or(|start| step >= start) && self.stop.is_none_or(|stop| step <= stop)
+    }
+
+    /// Same as `contains` for a step which was not written as an integer
+    fn contains_value(&self, step: ThermoValue) -> bool {
+        match step {
+            ThermoValue::Int(step) => self.contains(step),
+            ThermoValue::Float(step) => {
+                self.start.is_none_or(|start| step >= start as f64)
+                    && self.stop.is_none_or(|stop| step <= stop as f64)
+            }
+        }
+    }
+
+    /// Returns a filter on the `Step` column, None if every step is kept
+    pub fn to_expr(self) -> Option<Expr> {
+        let start: Option<Expr> = self.start.map(|start| col(STEP_COLUMN).gt_eq(lit(start)));
+        let stop: Option<Expr> = self.stop.map(|stop| col(STEP_COLUMN).lt_eq(lit(stop)));
+        match (start, stop) {
+            (Some(start), Some(stop)) => Some(start.and(stop)),
+            (start, stop) => start.or(stop),
+        }
+    }
+}
+
+/// Columns and steps of a thermo run which are kept while it is parsed
+#[derive(Clone, Debug, Default)]
+struct ThermoProjection {
+    /// None keeps every column
+    columns: Option<Vec<String>>,
+    steps: StepRange,
+}
+
+impl ThermoProjection {
+    fn keeps_column(&self, name: &str) -> bool {
+        self.columns
+            .as_ref()
+            .is_none_or(|columns| columns.iter().any(|column| column == name))
+    }
+
+    /// Applies the projection to a thermo run which was parsed without it
+    fn apply(&self, log_data: DataFrame) -> PolarsResult<DataFrame> {
+        let mut lazy_data: LazyFrame = log_data.clone().lazy();
+        if let Some(steps) = self.steps.to_expr()
+            && log_data.get_column_index(STEP_COLUMN).is_some()
+        {
+            lazy_data = lazy_data.filter(steps);
+        }
+        if let Some(columns) = &self.columns {
+            let columns: Vec<Expr> = log_data
+                .get_column_names()
+                .into_iter()
+                .filter(|name| columns.iter().any(|column| column == name.as_str()))
+                .map(|name| col(name.clone()))
+                .collect();
+            lazy_data = lazy_data.select(columns);
+        }
+        lazy_data.collect()
+    }
+}
+
+/// A thermo run parsed with a projection
+struct ParsedThermoRun {
+    log_data: DataFrame,
+    skipped_rows: Vec<SkippedRow>,
+    /// Number of valid rows of the run, including the ones left out by the projection
+    rows: usize,
+}
+
+impl ParsedThermoRun {
+    /// Applies the projection to a thermo run which was parsed without it
+    fn project(
+        (log_data, skipped_rows): (DataFrame, Vec<SkippedRow>),
+        projection: &ThermoProjection,
+    ) -> PolarsResult<ParsedThermoRun> {
+        Ok(ParsedThermoRun {
+            rows: log_data.height(),
+            log_data: projection.apply(log_data)?,
+            skipped_rows,
+        })
+    }
+}
+
+/// Polars scan over a thermo run, which passes the selected columns and the steps to the parser
+struct ThermoScan {
+    log_file_name: PathBuf,
+    thermo_run_id: u32,
+    /// Schema of the whole thermo run
+    schema: SchemaRef,
+}
+
+impl AnonymousScan for ThermoScan {
+    fn as_any(&self) -> &dyn std::any::Any {
+        self
+    }
+
+    fn scan(&self, scan_opts: AnonymousScanArgs) -> PolarsResult<DataFrame> {
+        let steps: StepRange = scan_opts
+            .predicate
+            .as_ref()
+            .map_or(StepRange::default(), StepRange::from_predicate);
+        let mut columns: Option<Vec<String>> = scan_opts
+            .with_columns
+            .as_ref()
+            .map(|with_columns| with_columns.iter().map(|name| name.to_string()).collect());
+        // The filter needs its columns even if they are not selected
+        if let (Some(columns), Some(predicate)) = (columns.as_mut(), &scan_opts.predicate) {
+            for expr in predicate {
+                if let Expr::Column(name) = expr
+                    && !columns.iter().any(|column| column == name.as_str())
+                {
+                    columns.push(name.to_string());
+                }
+            }
+        }
+
+        let reader = LogLammpsReader {
+            log_file_name: self.log_file_name.clone(),
+            mmap: false,
+        };
+        let projection = ThermoProjection { columns, steps };
+        let log_data: DataFrame =
+            match reader.parse_scanned_run(self.thermo_run_id, &self.schema, &projection) {
+                Ok(log_data) => log_data,
+                // Taken out again by `LammpsError::from_polars`
+                Err(e) => {
+                    return Err(PolarsError::IO {
+                        error: Arc::new(std::io::Error::other(reader.lammps_error(e))),
+                        msg: None,
+                    });
+                }
+            };
+
+        let mut lazy_data: LazyFrame = log_data.lazy();
+        if let Some(predicate) = scan_opts.predicate {
+            lazy_data = lazy_data.filter(predicate);
+        }
+        if let Some(with_columns) = scan_opts.with_columns {
+            lazy_data = lazy_data.select(
+                with_columns
+                    .iter()
+                    .map(|name| col(name.clone()))
+                    .collect::<Vec<Expr>>(),
+            );
+        }
+        lazy_data.collect()
+    }
+
+    fn schema(&self, _infer_schema_length: Option<usize>) -> PolarsResult<SchemaRef> {
+        Ok(self.schema.clone())
+    }
+
+    fn allows_predicate_pushdown(&self) -> bool {
+        true
+    }
+
+    fn allows_projection_pushdown(&self) -> bool {
+        true
+    }
+}
+
+/// Thermo rows parsed from a line-aligned chunk of the data lines of a thermo run
+struct ThermoChunk {
+    /// The values of the columns which are kept, the others stay empty
+    columns: Vec<ColumnBuffer>,
+    /// Number of valid rows, including the ones outside of the step range
+    rows: usize,
+    skipped_rows: Vec<SkippedRow>,
+}
+
+impl ThermoChunk {
+    /** Parses the rows of `thermo_style one/custom` output straight into column buffers.
+
+    Rows are checked like `check_row` before any of their values are stored. Only
+    the columns in `keep` and the rows in the step range are stored. `first_line` is
+    the line number of the first line of the chunk. */
+    fn parse(
+        text: &str,
+        log_header: &[String],
+        first_line: usize,
+        keep: &[bool],
+        steps: Option<(usize, StepRange)>,
+    ) -> ThermoChunk {
+        let mut columns: Vec<ColumnBuffer> = vec![ColumnBuffer::Int(Vec::new()); log_header.len()];
+        let mut rows: usize = 0;
+        let mut skipped_rows: Vec<SkippedRow> = Vec::new();
+        let mut row: Vec<ThermoValue> = Vec::with_capacity(log_header.len());
+
+        for (index, line) in text.lines().enumerate() {
+            if is_ignored_line(line) {
+                continue;
+            }
+            row.clear();
+            let mut found: usize = 0;
+            let mut invalid_value: Option<(usize, &str)> = None;
+            for value in line.split_whitespace() {
+                match ThermoValue::parse(value) {
+                    Some(number) => row.push(number),
+                    None if invalid_value.is_none() => invalid_value = Some((found, value)),
+                    None => {}
+                }
+                found += 1;
+            }
+
+            let reason: Option<SkipReason> = if found != log_header.len() {
+                Some(SkipReason::ColumnCount {
+                    expected: log_header.len(),
+                    found,
+                })
+            } else {
+                invalid_value.map(|(column, value)| SkipReason::InvalidValue {
+                    column: log_header[column].clone(),
+                    value: value.to_string(),
+                })
+            };
+            if let Some(reason) = reason {
+                skipped_rows.push(SkippedRow {
+                    line: first_line + index,
+                    reason,
+                    text: line.to_string(),
+                });
+                continue;
+            }
+
+            rows += 1;
+            if steps.is_some_and(|(column, steps)| !steps.contains_value(row[column])) {
+                continue;
+            }
+            for ((column, &value), &keep) in columns.iter_mut().zip(&row).zip(keep) {
+                if keep {
+                    column.push(value);
+                }
+            }
+        }
+
+        ThermoChunk {
+            columns,
+            rows,
+            skipped_rows,
+        }
+    }
+}
+
+/// Reason for skipping a row of thermo output
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum SkipReason {
+    /// The number of values does not match the number of columns
+    ColumnCount { expected: usize, found: usize },
+    /// A value which is not a number
+    InvalidValue { column: String, value: String },
+    /// A `thermo_style multi` block without a value for a column
+    MissingColumn { column: String },
+}
+
+impl std::fmt::Display for SkipReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SkipReason::ColumnCount { expected, found } => {
+                write!(f, "expected {} values but found {}", expected, found)
+            }
+            SkipReason::InvalidValue { column, value } => {
+                write!(f, "invalid value '{}' in column '{}'", value, column)
+            }
+            SkipReason::MissingColumn { column } => write!(f, "missing column '{}'", column),
+        }
+    }
+}
+
+/// Error for a thermo run which could not be parsed, with the line where parsing stopped
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ThermoParseError {
+    /// The run ended before a line with thermo keywords, `line` is the first line
+    /// after `Per MPI rank memory allocation` which is not a thermo header
+    MissingHeader { line: usize, text: String },
+    /// Every row of the run was skipped, `line` is the first skipped row
+    NoValidRows {
+        line: usize,
+        text: String,
+        reason: SkipReason,
+    },
+}
+
+impl std::fmt::Display for ThermoParseError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ThermoParseError::MissingHeader { line, text } => write!(
+                f,
+                "No thermo header found for the run, line {} does not have thermo keywords: '{}'",
+                line, text
+            ),
+            ThermoParseError::NoValidRows { line, text, reason } => write!(
+                f,
+                "No row of the thermo run could be parsed, the first at line {} ({}): '{}'",
+                line, reason, text
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ThermoParseError {}
+
+/// A row of thermo output which was skipped while parsing
+#[derive(Clone, Debug)]
+pub struct SkippedRow {
+    /// Line number of the row (starting at 1)
+    pub line: usize,
+    pub reason: SkipReason,
+    pub text: String,
+}
+
+impl SkippedRow {
+    /// Converts the skipped rows into a DataFrame with the `line`, `reason` and `text` columns
+    pub fn to_df(skipped_rows: &[SkippedRow]) -> PolarsResult<DataFrame> {
+        df![
+            "line" => skipped_rows.iter().map(|row| row.line as u64).collect::<Vec<u64>>(),
+            "reason" => skipped_rows
+                .iter()
+                .map(|row| row.reason.to_string())
+                .collect::<Vec<String>>(),
+            "text" => skipped_rows.iter().map(|row| row.text.as_str()).collect::<Vec<&str>>(),
+        ]
+    }
+}
+
+/// Layout of the thermo output, given by `thermo_style` and `thermo_modify line`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum ThermoStyle {
+    /// A header line followed by one row per step (`one` and `custom`)
+    One,
+    /// `---- Step N ----` blocks of `Key = value` pairs
+    Multi,
+    /// YAML documents with `keywords:` and `data:`
+    Yaml,
+}
+
+impl ThermoStyle {
+    /// Detects the thermo style from the first line of a thermo run
+    fn detect(log_header_str: &str) -> ThermoStyle {
+        if is_multi_step_line(log_header_str) {
+            ThermoStyle::Multi
+        } else if log_header_str.trim() == YAML_START_FLAG {
+            ThermoStyle::Yaml
+        } else {
+            ThermoStyle::One
+        }
+    }
+
+    /** Checks if the data lines read so far give the columns of a thermo run, which
+    are in the header for `one`, the first block for `multi` and the `keywords:` line
+    for `yaml`. */
+    fn has_columns(self, data_lines: &[String]) -> bool {
+        match self {
+            ThermoStyle::One => true,
+            ThermoStyle::Multi => data_lines
+                .last()
+                .is_some_and(|line| is_multi_step_line(line)),
+            ThermoStyle::Yaml => data_lines
+                .last()
+                .is_some_and(|line| line.trim().starts_with(YAML_KEYWORDS_FLAG)),
+        }
+    }
+}
+
+/// The command which produced a thermo run
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ThermoRunKind {
+    Minimize,
+    Run,
+    Unknown,
+}
+
+impl ThermoRunKind {
+    /// Returns the kind of run started by an input command, if any
+    fn from_command(line: &str) -> Option<ThermoRunKind> {
+        let command: &str = line.split_whitespace().next()?;
+        if command == "minimize" || command.starts_with("minimize/") {
+            Some(ThermoRunKind::Minimize)
+        } else if command == "run" || command == "rerun" {
+            Some(ThermoRunKind::Run)
+        } else {
+            None
+        }
+    }
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ThermoRunKind::Minimize => "minimize",
+            ThermoRunKind::Run => "run",
+            ThermoRunKind::Unknown => "unknown",
+        }
+    }
+}
+
+/// Summary of a single thermo run in the log file
+#[derive(Clone, Debug)]
+pub struct ThermoRunInfo {
+    pub run_id: u32,
+    pub kind: ThermoRunKind,
+    /// Thermo columns of the run
+    pub columns: Vec<String>,
+    /// Number of valid thermo rows
+    pub rows: usize,
+    pub first_step: Option<u64>,
+    pub last_step: Option<u64>,
+    /// Line number of the first line of thermo output (starting at 1)
+    pub start_line: usize,
+    /// Line number of the `Loop time` or `ERROR` line, or the last line for an unfinished run
+    pub end_line: usize,
+    style: ThermoStyle,
+}
+
+impl ThermoRunInfo {
+    fn new(run_id: u32, kind: ThermoRunKind, start_line: usize, log_header_str: &str) -> Self {
+        let style: ThermoStyle = ThermoStyle::detect(log_header_str);
+        let mut run = ThermoRunInfo {
+            run_id,
+            kind,
+            columns: Vec::new(),
+            rows: 0,
+            first_step: None,
+            last_step: None,
+            start_line,
+            end_line: start_line,
+            style,
+        };
+        match style {
+            ThermoStyle::One => {
+                run.columns = log_header_str
+                    .split_whitespace()
+                    .map(String::from)
+                    .collect()
+            }
+            ThermoStyle::Multi => run.add_line(log_header_str),
+            ThermoStyle::Yaml => {}
+        }
+        run
+    }
+
+    /// Updates the columns, row count and step range with a line of thermo output
+    fn add_line(&mut self, line: &str) {
+        let step: Option<u64> = match self.style {
+            ThermoStyle::One => {
+                let row: Vec<&str> = line.split_whitespace().collect();
+                if check_row(&row, &self.columns).is_err() {
+                    return;
+                }
+                self.step_value(&row)
+            }
+            ThermoStyle::Multi => {
+                let key_values: Vec<(String, String)> = multi_key_values(line);
+                if !is_multi_step_line(line) {
+                    if self.rows == 1 {
+                        self.columns
+                            .extend(key_values.into_iter().map(|(key, _)| key));
+                    }
+                    return;
+                }
+                if self.rows == 0 {
+                    self.columns
+                        .extend(key_values.iter().map(|(key, _)| key.clone()));
+                }
+                key_values
+                    .iter()
+                    .find(|(key, _)| key == "Step")
+                    .and_then(|(_, value)| value.parse::<u64>().ok())
+            }
+            ThermoStyle::Yaml => {
+                let line: &str = line.trim();
+                if let Some(keywords) = line.strip_prefix(YAML_KEYWORDS_FLAG) {
+                    self.columns = yaml_flow_sequence(keywords);
+                    return;
+                }
+                if !line.starts_with(YAML_ROW_FLAG) {
+                    return;
+                }
+                let row: Vec<String> = yaml_flow_sequence(&line[1..]);
+                if check_row(&row, &self.columns).is_err() {
+                    return;
+                }
+                self.step_value(&row)
+            }
+        };
+
+        self.rows += 1;
+        if self.first_step.is_none() {
+            self.first_step = step;
+        }
+        if step.is_some() {
+            self.last_step = step;
+        }
+    }
+
+    /// Returns the value in the `Step` column of a row
+    fn step_value<S: AsRef<str>>(&self, row: &[S]) -> Option<u64> {
+        let index: usize = self.columns.iter().position(|column| column == "Step")?;
+        row.get(index)?.as_ref().parse::<u64>().ok()
+    }
+}
+
+/// Severity of a message printed by LAMMPS
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DiagnosticLevel {
+    Warning,
+    Error,
+}
+
+impl DiagnosticLevel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            DiagnosticLevel::Warning => "WARNING",
+            DiagnosticLevel::Error => "ERROR",
+        }
+    }
+}
+
+/// A `WARNING` or `ERROR` message in the log file
+#[derive(Clone, Debug)]
+pub struct LogDiagnostic {
+    /// Line number of the message (starting at 1)
+    pub line: usize,
+    pub level: DiagnosticLevel,
+    /// The thermo run in which the message was printed, `None` if it was outside of thermo output
+    pub run_id: Option<u32>,
+    /// The last thermo step printed before the message
+    pub last_step: Option<u64>,
+    /// The message without the `WARNING:` prefix and the source location
+    pub message: String,
+    /// Source file location reported by LAMMPS, e.g. `src/domain.cpp:1102`
+    pub location: Option<String>,
+}
+
+impl LogDiagnostic {
+    /// Parses a `WARNING: ... (src/file.cpp:123)` or `ERROR on proc 0: ...` line
+    fn from_line(line: &str) -> Option<(DiagnosticLevel, String, Option<String>)> {
+        let line: &str = line.trim();
+        let level: DiagnosticLevel = if line.starts_with(WARNING_FLAG) {
+            DiagnosticLevel::Warning
+        } else if line.starts_with(ERROR_FLAGS[1]) {
+            DiagnosticLevel::Error
+        } else {
+            return None;
+        };
+        // Matches `WARNING:`, `ERROR:` and `ERROR on proc 0:`
+        let (prefix, message) = line.split_once(':')?;
+        if prefix != level.as_str() && !prefix.starts_with(&format!("{} on proc", level.as_str())) {
+            return None;
+        }
+
+        let message: &str = message.trim();
+        let location: Option<&str> = message
+            .strip_suffix(')')
+            .and_then(|message| message.rsplit_once('('))
+            .map(|(_, location)| location)
+            .filter(|location| {
+                location
+                    .rsplit_once(':')
+                    .is_some_and(|(_, number)| number.parse::<u32>().is_ok())
+            });
+        let message: &str = match location {
+            Some(location) => message[..message.len() - location.len() - 2].trim_end(),
+            None => message,
+        };
+
+        Some((level, message.to_string(), location.map(String::from)))
+    }
+}
 
 impl LogLammpsReader {
     /** Constructor to create a new instance of LogLammpsReader.
@@ -23,13 +970,150 @@ impl LogLammpsReader {
     log_file_name: File path for the LAMMPS log file
     requried_thermo_run_id: The index of the run thermo (default = 0)
 
-    Returns a polars DataFrame object*/
+    Returns a polars DataFrame object. Columns where every value is an
+    integer (e.g. `Step` and `Atoms`) are i64, the others are f64.
+
+    The log file is streamed, see `parse_mapped_with_report` to memory map it. */
     pub fn parse(
         log_file_name: PathBuf,
         requried_thermo_run_id: Option<u32>,
-    ) -> Result<DataFrame, Box<dyn std::error::Error>> {
-        LogLammpsReader { log_file_name }
+    ) -> Result<DataFrame, LammpsError> {
+        Ok(LogLammpsReader::parse_with_report(log_file_name, requried_thermo_run_id)?.0)
+    }
+
+    /** Same as `parse` but also reports the rows of the thermo run which were skipped.
+
+    Rows are skipped when their number of values does not match the header or when
+    a value is not a number. `nan` and `inf` values are kept. Blank lines and
+    warnings printed in the middle of the run are not reported.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+    requried_thermo_run_id: The index of the run thermo (default = 0)
+
+    Returns a polars DataFrame object and the skipped rows */
+    pub fn parse_with_report(
+        log_file_name: PathBuf,
+        requried_thermo_run_id: Option<u32>,
+    ) -> Result<(DataFrame, Vec<SkippedRow>), LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .parse_lammps_log(requried_thermo_run_id.unwrap_or_default())
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /** Same as `parse_with_report` but memory maps an uncompressed log file instead of
+    streaming it, so that the rows are parsed in parallel without copying the lines.
+    Compressed log files are streamed.
+
+    ### Safety
+    The file must not be truncated while it is parsed, e.g. by a new LAMMPS run whose
+    `log` command opens the same file name. Reading the bytes which were cut off is
+    undefined behavior, which kills the process with SIGBUS. Appending to the file
+    is safe, the appended lines are not read. */
+    pub unsafe fn parse_mapped_with_report(
+        log_file_name: PathBuf,
+        requried_thermo_run_id: Option<u32>,
+    ) -> Result<(DataFrame, Vec<SkippedRow>), LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: true,
+        };
+        reader
             .parse_lammps_log(requried_thermo_run_id.unwrap_or_default())
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /** Same as `parse` but fails if any row of the thermo run is skipped.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+    requried_thermo_run_id: The index of the run thermo (default = 0)
+
+    Returns a polars DataFrame object*/
+    pub fn parse_strict(
+        log_file_name: PathBuf,
+        requried_thermo_run_id: Option<u32>,
+    ) -> Result<DataFrame, LammpsError> {
+        let parsed: (DataFrame, Vec<SkippedRow>) =
+            LogLammpsReader::parse_with_report(log_file_name.clone(), requried_thermo_run_id)?;
+        LogLammpsReader::reject_skipped_rows(&log_file_name, parsed)
+    }
+
+    /** Returns the thermo data of `parse_with_report`, or the error of `parse_strict`
+    if any row was skipped.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+    (log_data, skipped_rows): The thermo data and the skipped rows of the log file
+
+    Returns a polars DataFrame object*/
+    pub fn reject_skipped_rows(
+        log_file_name: &Path,
+        (log_data, skipped_rows): (DataFrame, Vec<SkippedRow>),
+    ) -> Result<DataFrame, LammpsError> {
+        match skipped_rows.first() {
+            Some(first) => Err(LammpsError::InvalidData {
+                path: log_file_name.to_path_buf(),
+                line: Some(first.line),
+                message: format!(
+                    "{} row(s) of thermo data could not be parsed, the first at line {} ({}): '{}'",
+                    skipped_rows.len(),
+                    first.line,
+                    first.reason,
+                    first.text
+                ),
+            }),
+            None => Ok(log_data),
+        }
+    }
+
+    /** Same as `parse` but casts the columns to the data types given in the schema.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+    requried_thermo_run_id: The index of the run thermo (default = 0)
+    schema: Data types of the columns which should not be inferred
+
+    Returns a polars DataFrame object*/
+    pub fn parse_with_schema(
+        log_file_name: PathBuf,
+        requried_thermo_run_id: Option<u32>,
+        schema: &Schema,
+    ) -> Result<DataFrame, LammpsError> {
+        let log_data: DataFrame =
+            LogLammpsReader::parse(log_file_name.clone(), requried_thermo_run_id)?;
+        LogLammpsReader::apply_schema(log_data, schema)
+            .map_err(|e| LammpsError::from_boxed(&log_file_name, e))
+    }
+
+    /** Creates a polars LazyFrame which parses a thermo run when it is collected.
+
+    The selected columns and filters on `Step` are passed to the parser, so the
+    other columns are never stored and rows outside of the steps are skipped.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+    requried_thermo_run_id: The index of the run thermo (default = 0)
+
+    Returns a polars LazyFrame whose schema is read from the header of the run: the
+    integer thermo keywords (e.g. `Step`, `Atoms`) are Int64 and the other columns
+    are Float64. The log file is streamed when the LazyFrame is collected, and its
+    errors are turned back into a LammpsError by `LammpsError::from_polars`. */
+    pub fn scan(
+        log_file_name: PathBuf,
+        requried_thermo_run_id: Option<u32>,
+    ) -> Result<LazyFrame, LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .scan_thermo_run(requried_thermo_run_id.unwrap_or_default())
+            .map_err(|e| reader.lammps_error(e))
     }
 
     /** Constructor to create a new instance of LogLammpsReader.
@@ -42,127 +1126,1227 @@ impl LogLammpsReader {
     pub fn log_starts_with(
         log_file_name: PathBuf,
         prefix_key: &str,
-    ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
-        LogLammpsReader { log_file_name }.parse_log_starts_with(prefix_key)
+    ) -> Result<Vec<String>, LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .parse_log_starts_with(prefix_key)
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /** Finds the lines of the log file which match any of several patterns.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+    patterns: Prefixes of the trimmed lines, or regular expressions with `regex`
+    regex: Match the patterns as regular expressions, which may have named
+        capture groups such as `fix (?P<fix_id>\S+)`
+
+    Returns a polars DataFrame with the `line` number, the `run_id` of the thermo
+    run (lines between runs belong to the next run), the index of the first
+    `pattern` which matched, the trimmed `text` of the line and a column for every
+    named capture group, which is null when the group was not matched. */
+    pub fn log_grep(
+        log_file_name: PathBuf,
+        patterns: &[String],
+        regex: bool,
+    ) -> Result<DataFrame, LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .grep_log(patterns, regex)
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /** Lists the thermo runs in the log file without storing their data.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a vector with the columns, row count, step range, command and
+    line numbers of each thermo run. */
+    pub fn list_runs(log_file_name: PathBuf) -> Result<Vec<ThermoRunInfo>, LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .list_lammps_log_runs()
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /// Same as `list_runs` but returns a polars DataFrame with one row for each thermo run
+    pub fn list_runs_df(log_file_name: PathBuf) -> Result<DataFrame, LammpsError> {
+        let runs: Vec<ThermoRunInfo> = LogLammpsReader::list_runs(log_file_name.clone())?;
+        let columns: Vec<Series> = runs
+            .iter()
+            .map(|run| Series::new("".into(), &run.columns))
+            .collect();
+        df![
+            "run_id" => runs.iter().map(|run| run.run_id).collect::<Vec<u32>>(),
+            "kind" => runs.iter().map(|run| run.kind.as_str()).collect::<Vec<&str>>(),
+            "columns" => columns,
+            "rows" => runs.iter().map(|run| run.rows as u64).collect::<Vec<u64>>(),
+            "first_step" => runs.iter().map(|run| run.first_step).collect::<Vec<Option<u64>>>(),
+            "last_step" => runs.iter().map(|run| run.last_step).collect::<Vec<Option<u64>>>(),
+            "start_line" => runs.iter().map(|run| run.start_line as u64).collect::<Vec<u64>>(),
+            "end_line" => runs.iter().map(|run| run.end_line as u64).collect::<Vec<u64>>(),
+        ]
+        .map_err(|source| LammpsError::Polars {
+            path: log_file_name,
+            source,
+        })
+    }
+
+    /** Parses the performance summary printed after each thermo run.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a vector with the loop time, `Performance:` rates, CPU use and
+    MPI task timing breakdown of every run which finished. */
+    pub fn run_performance(log_file_name: PathBuf) -> Result<Vec<RunPerformance>, LammpsError> {
+        let mut performance: Vec<RunPerformance> = Vec::new();
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .for_each_run_footer(|thermo_run_id, footer| {
+                performance.extend(RunPerformance::from_footer(thermo_run_id, &footer));
+            })
+            .map_err(|e| reader.lammps_error(e))?;
+        Ok(performance)
+    }
+
+    /** Parses the atom and neighbor list statistics printed after each thermo run.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a vector with the `Nlocal`, `Nghost` and `Neighs` distributions,
+    neighbor counts and (dangerous) neighbor list builds of every finished run. */
+    pub fn neighbor_stats(log_file_name: PathBuf) -> Result<Vec<NeighborStats>, LammpsError> {
+        let mut stats: Vec<NeighborStats> = Vec::new();
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .for_each_run_footer(|thermo_run_id, footer| {
+                stats.extend(NeighborStats::from_footer(thermo_run_id, &footer));
+            })
+            .map_err(|e| reader.lammps_error(e))?;
+        Ok(stats)
+    }
+
+    /// Same as `neighbor_stats` but returns a polars DataFrame with one row for each run
+    pub fn neighbor_stats_df(log_file_name: PathBuf) -> Result<DataFrame, LammpsError> {
+        let stats: Vec<NeighborStats> = LogLammpsReader::neighbor_stats(log_file_name.clone())?;
+        NeighborStats::to_df(&stats).map_err(|source| LammpsError::Polars {
+            path: log_file_name,
+            source,
+        })
+    }
+
+    /** Parses the `Minimization stats:` block printed after each `minimize` run.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a vector with the stopping criterion, energies, forces, iterations
+    and force evaluations of every finished minimization along with its run id. */
+    pub fn minimization_stats(
+        log_file_name: PathBuf,
+    ) -> Result<Vec<MinimizationStats>, LammpsError> {
+        let mut stats: Vec<MinimizationStats> = Vec::new();
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .for_each_run_footer(|thermo_run_id, footer| {
+                stats.extend(MinimizationStats::from_footer(thermo_run_id, &footer));
+            })
+            .map_err(|e| reader.lammps_error(e))?;
+        Ok(stats)
+    }
+
+    /// Same as `minimization_stats` but returns a polars DataFrame with one row for each run
+    pub fn minimization_stats_df(log_file_name: PathBuf) -> Result<DataFrame, LammpsError> {
+        let stats: Vec<MinimizationStats> =
+            LogLammpsReader::minimization_stats(log_file_name.clone())?;
+        MinimizationStats::to_df(&stats).map_err(|source| LammpsError::Polars {
+            path: log_file_name,
+            source,
+        })
+    }
+
+    /** Collects every `WARNING` and `ERROR` message in the log file.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a vector with the line number, thermo run, last thermo step,
+    message and source location of each message. */
+    pub fn diagnostics(log_file_name: PathBuf) -> Result<Vec<LogDiagnostic>, LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .parse_log_diagnostics()
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /// Same as `diagnostics` but returns a polars DataFrame with one row for each message
+    pub fn diagnostics_df(log_file_name: PathBuf) -> Result<DataFrame, LammpsError> {
+        let diagnostics: Vec<LogDiagnostic> = LogLammpsReader::diagnostics(log_file_name.clone())?;
+        df![
+            "line" => diagnostics.iter().map(|d| d.line as u64).collect::<Vec<u64>>(),
+            "level" => diagnostics.iter().map(|d| d.level.as_str()).collect::<Vec<&str>>(),
+            "run_id" => diagnostics.iter().map(|d| d.run_id).collect::<Vec<Option<u32>>>(),
+            "last_step" => diagnostics.iter().map(|d| d.last_step).collect::<Vec<Option<u64>>>(),
+            "message" => diagnostics.iter().map(|d| d.message.as_str()).collect::<Vec<&str>>(),
+            "location" => diagnostics
+                .iter()
+                .map(|d| d.location.as_deref())
+                .collect::<Vec<Option<&str>>>(),
+        ]
+        .map_err(|source| LammpsError::Polars {
+            path: log_file_name,
+            source,
+        })
+    }
+
+    /** Rebuilds the input script from the commands echoed in the log file.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a vector with the line number, the thermo run which follows, the raw
+    text and the text after substituting the variables of every command. */
+    pub fn input_commands(log_file_name: PathBuf) -> Result<Vec<InputCommand>, LammpsError> {
+        let mut input_script: InputScript = InputScript::default();
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .for_each_line(|line| {
+                if line.section == LogSection::Input {
+                    input_script.add_line(line.number, line.thermo_run_id, line.text);
+                }
+                Ok(true)
+            })
+            .map_err(|e| reader.lammps_error(e))?;
+        Ok(input_script.finish())
+    }
+
+    /// Same as `input_commands` but returns a polars DataFrame with one row for each command
+    pub fn input_commands_df(log_file_name: PathBuf) -> Result<DataFrame, LammpsError> {
+        let commands: Vec<InputCommand> = LogLammpsReader::input_commands(log_file_name.clone())?;
+        InputCommand::to_df(&commands).map_err(|source| LammpsError::Polars {
+            path: log_file_name,
+            source,
+        })
+    }
+
+    /** Collects the version and settings of the simulation from the log file.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns the LAMMPS version, the units, atom style, dimension, boundary,
+    timestep, number of atoms, simulation box and MPI/OpenMP layout at the end
+    of the log file, along with the settings which applied to each thermo run. */
+    pub fn log_metadata(log_file_name: PathBuf) -> Result<LogMetadata, LammpsError> {
+        let mut metadata: LogMetadata = LogMetadata::default();
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .for_each_line(|line| {
+                match line.section {
+                    LogSection::Input => metadata.update(line.text),
+                    LogSection::Header => metadata.start_run(line.thermo_run_id),
+                    LogSection::Thermo | LogSection::End => {}
+                }
+                Ok(true)
+            })
+            .map_err(|e| reader.lammps_error(e))?;
+        Ok(metadata)
+    }
+
+    /** Parses every thermo run in the log file while reading the file only once.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a vector of polars DataFrame objects where the index is the thermo run id.
+    Runs without any valid data are returned as empty DataFrames. */
+    pub fn parse_all(log_file_name: PathBuf) -> Result<Vec<DataFrame>, LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: false,
+        };
+        reader
+            .parse_all_lammps_log()
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /** Same as `parse_all` but memory maps an uncompressed log file instead of streaming it.
+
+    ### Safety
+    The file must not be truncated while it is parsed (see `parse_mapped_with_report`). */
+    pub unsafe fn parse_all_mapped(log_file_name: PathBuf) -> Result<Vec<DataFrame>, LammpsError> {
+        let reader = LogLammpsReader {
+            log_file_name,
+            mmap: true,
+        };
+        reader
+            .parse_all_lammps_log()
+            .map_err(|e| reader.lammps_error(e))
+    }
+
+    /** Parses every thermo run in the log file while reading the file only once.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a single polars DataFrame with a `run_id` column. When the thermo columns
+    differ between runs, the columns are merged and missing values are null. */
+    pub fn parse_all_concat(log_file_name: PathBuf) -> Result<DataFrame, LammpsError> {
+        let log_data: Vec<DataFrame> = LogLammpsReader::parse_all(log_file_name.clone())?;
+        concat_runs(log_data).map_err(|source| LammpsError::Polars {
+            path: log_file_name,
+            source,
+        })
     }
 
-    /// Returns a BufReader for a certain file
+    /** Same as `parse_all_concat` but memory maps an uncompressed log file instead of streaming it.
+
+    ### Safety
+    The file must not be truncated while it is parsed (see `parse_mapped_with_report`). */
+    pub unsafe fn parse_all_concat_mapped(
+        log_file_name: PathBuf,
+    ) -> Result<DataFrame, LammpsError> {
+        // SAFETY: the caller upholds the contract of `parse_all_mapped`
+        let log_data: Vec<DataFrame> =
+            unsafe { LogLammpsReader::parse_all_mapped(log_file_name.clone())? };
+        concat_runs(log_data).map_err(|source| LammpsError::Polars {
+            path: log_file_name,
+            source,
+        })
+    }
+
+    /** Joins thermo runs of several log files into one timeline, e.g. a simulation
+    which was continued from restart files in a new log file.
+
+    ### Parameters:
+    logs: Log files in the order of the simulation, each with the ids of the
+        thermo runs to add (None adds every run with thermo data)
+    drop_overlap: Drop rows which are repeated by a later run, where a restart
+        overlaps the previous log or a run starts at the last step of the previous run
+
+    Returns a single polars DataFrame with `source_file` and `run_id` columns.
+    `Step` and `Time` continue across `reset_timestep` commands, so they only
+    decrease where the runs overlap. */
+    pub fn stitch(
+        logs: Vec<(PathBuf, Option<Vec<u32>>)>,
+        drop_overlap: bool,
+    ) -> Result<DataFrame, LammpsError> {
+        // Errors of the timeline itself are reported for the first log file
+        let first_log_file_name: PathBuf = logs
+            .first()
+            .map(|(log_file_name, _)| log_file_name.clone())
+            .unwrap_or_default();
+        let mut segments: Vec<TimelineSegment> = Vec::new();
+        for (log_file_name, thermo_run_ids) in logs {
+            let source_file: String = log_file_name.display().to_string();
+            let reader = LogLammpsReader {
+                log_file_name,
+                mmap: false,
+            };
+            let reset_run_ids: Vec<u32> = reader
+                .reset_timestep_run_ids()
+                .map_err(|e| reader.lammps_error(e))?;
+            let runs: Vec<(u32, DataFrame)> = match thermo_run_ids {
+                Some(thermo_run_ids) => thermo_run_ids
+                    .into_iter()
+                    .map(|thermo_run_id| {
+                        Ok((thermo_run_id, reader.parse_lammps_log(thermo_run_id)?.0))
+                    })
+                    .collect::<Result<Vec<_>, Box<dyn std::error::Error>>>()
+                    .map_err(|e| reader.lammps_error(e))?,
+                None => (0..)
+                    .zip(
+                        reader
+                            .parse_all_lammps_log()
+                            .map_err(|e| reader.lammps_error(e))?,
+                    )
+                    .filter(|(_, df)| df.height() > 0)
+                    .collect(),
+            };
+
+            let mut previous_run_id: Option<u32> = None;
+            for (run_id, log_data) in runs {
+                let reset_timestep: bool = reset_run_ids.iter().any(|&reset_run_id| {
+                    reset_run_id <= run_id && previous_run_id.is_none_or(|id| reset_run_id > id)
+                });
+                segments.push(TimelineSegment {
+                    source_file: source_file.clone(),
+                    run_id,
+                    reset_timestep,
+                    log_data,
+                });
+                previous_run_id = Some(run_id);
+            }
+        }
+        stitch_segments(segments, drop_overlap)
+            .map_err(|e| LammpsError::from_boxed(&first_log_file_name, e))
+    }
+
+    /** Creates a reader which follows a log file while the simulation is still writing it.
+
+    ### Parameters:
+    log_file_name: File path for the LAMMPS log file
+
+    Returns a LogTail, nothing is read until `poll` is called. */
+    pub fn tail(log_file_name: PathBuf) -> LogTail {
+        LogTail {
+            reader: LogLammpsReader {
+                log_file_name,
+                mmap: false,
+            },
+            scanner: LogScanner::default(),
+            offset: 0,
+            partial_line: Vec::new(),
+            current_run: None,
+            run_id: None,
+            run_data: DataFrame::empty(),
+        }
+    }
+
+    /// Opens a log file
+    fn log_file(log_file_name: &PathBuf) -> Result<File, Box<dyn std::error::Error>> {
+        Ok(File::open(log_file_name).map_err(|error| {
+            LammpsError::from_open(
+                log_file_name,
+                error,
+                &format!(
+                    "Log file at '{}' not found...\nCheck 'log_file_name' parameter",
+                    log_file_name.display()
+                ),
+            )
+        })?)
+    }
+
+    /// Converts an error of the parsers into a LammpsError of this log file
+    fn lammps_error(&self, error: Box<dyn std::error::Error>) -> LammpsError {
+        LammpsError::from_boxed(&self.log_file_name, error)
+    }
+
+    /// Creates the LazyFrame of `scan` for a thermo run
+    fn scan_thermo_run(&self, thermo_run_id: u32) -> Result<LazyFrame, Box<dyn std::error::Error>> {
+        let schema: SchemaRef = Arc::new(self.thermo_run_schema(thermo_run_id)?);
+
+        let columns: Vec<Expr> = schema.iter_names().map(|name| col(name.clone())).collect();
+        let thermo_scan = ThermoScan {
+            log_file_name: self.log_file_name.clone(),
+            thermo_run_id,
+            schema: schema.clone(),
+        };
+        let scan_args = ScanArgsAnonymous {
+            schema: Some(schema),
+            name: "scan_log",
+            ..Default::default()
+        };
+        // Without a projection, the projection pushdown of polars fails on anonymous scans
+        Ok(LazyFrame::anonymous_scan(Arc::new(thermo_scan), scan_args)?.select(columns))
+    }
+
+    /// Finds the lines which match the patterns of `log_grep`
+    fn grep_log(
+        &self,
+        patterns: &[String],
+        regex: bool,
+    ) -> Result<DataFrame, Box<dyn std::error::Error>> {
+        let patterns: Vec<Regex> = patterns
+            .iter()
+            .map(|pattern| {
+                let pattern: String = if regex {
+                    pattern.clone()
+                } else {
+                    format!("^{}", regex::escape(pattern))
+                };
+                Regex::new(&pattern).map_err(|e| format!("Invalid pattern '{}': {}", pattern, e))
+            })
+            .collect::<Result<Vec<Regex>, String>>()?;
+        let mut capture_names: Vec<String> = Vec::new();
+        for name in patterns
+            .iter()
+            .flat_map(|pattern| pattern.capture_names().flatten())
+        {
+            if !capture_names
+                .iter()
+                .any(|capture_name| capture_name == name)
+            {
+                capture_names.push(name.to_string());
+            }
+        }
+
+        let mut lines: Vec<u64> = Vec::new();
+        let mut run_ids: Vec<u32> = Vec::new();
+        let mut pattern_ids: Vec<u32> = Vec::new();
+        let mut texts: Vec<String> = Vec::new();
+        let mut captures: Vec<Vec<Option<String>>> = vec![Vec::new(); capture_names.len()];
+        self.for_each_line(|line| {
+            let text: &str = line.text.trim();
+            let Some((pattern_id, matched)) = patterns
+                .iter()
+                .enumerate()
+                .find_map(|(pattern_id, pattern)| Some((pattern_id, pattern.captures(text)?)))
+            else {
+                return Ok(true);
+            };
+            lines.push(line.number as u64);
+            run_ids.push(line.thermo_run_id);
+            pattern_ids.push(pattern_id as u32);
+            texts.push(text.to_string());
+            for (name, values) in capture_names.iter().zip(captures.iter_mut()) {
+                values.push(matched.name(name).map(|value| value.as_str().to_string()));
+            }
+            Ok(true)
+        })?;
+
+        let mut columns: Vec<Column> = vec![
+            Column::new("line".into(), lines),
+            Column::new("run_id".into(), run_ids),
+            Column::new("pattern".into(), pattern_ids),
+            Column::new("text".into(), texts),
+        ];
+        for (name, values) in capture_names.iter().zip(captures) {
+            columns.push(Column::new(name.into(), values));
+        }
+        Ok(DataFrame::new(columns)?)
+    }
+
+    /// Returns a buffered reader for a certain file, decompressing it if it is gzip, xz or zstd compressed
     fn log_buffer_reader(
         log_file_name: &PathBuf,
-    ) -> Result<BufReader<File>, Box<dyn std::error::Error>> {
-        let log_file: File = File::open(log_file_name).map_err(|_| {
-            format!(
-                "Log file at '{}' not found...\nCheck 'log_file_name' parameter",
-                log_file_name.display()
-            )
+    ) -> Result<Box<dyn BufRead + Send>, Box<dyn std::error::Error>> {
+        let log_file: File = LogLammpsReader::log_file(log_file_name)?;
+        Ok(decompressed_reader(log_file)?)
+    }
+
+    /// Parses a thermo run of `scan` with a projection and casts its columns to the schema
+    fn parse_scanned_run(
+        &self,
+        thermo_run_id: u32,
+        schema: &Schema,
+        projection: &ThermoProjection,
+    ) -> Result<DataFrame, Box<dyn std::error::Error>> {
+        let mut log_data: DataFrame = match self.parse_thermo_run(thermo_run_id, projection)? {
+            Some(parsed) if parsed.rows > 0 => parsed.log_data,
+            // A run without valid rows has the columns of its header
+            _ => projection.apply(DataFrame::empty_with_schema(schema))?,
+        };
+
+        // The schema is taken from the header, and the parser infers the data types from the values
+        for (name, dtype) in schema.iter() {
+            if let Ok(column) = log_data.column(name)
+                && column.dtype() != dtype
+            {
+                let column: Column = column.cast(dtype)?;
+                log_data.with_column(column)?;
+            }
+        }
+        Ok(log_data)
+    }
+
+    /** Finds the schema of a thermo run from its header, without reading its rows.
+
+    Reading stops once the columns are known. See `thermo_schema` for the data
+    types of the columns. */
+    fn thermo_run_schema(&self, thermo_run_id: u32) -> Result<Schema, Box<dyn std::error::Error>> {
+        let mut run: Option<(String, Vec<String>)> = None;
+        self.for_each_line(|line| {
+            if line.thermo_run_id != thermo_run_id {
+                return Ok(line.thermo_run_id < thermo_run_id);
+            }
+            match (line.section, run.as_mut()) {
+                (LogSection::Header, _) => run = Some((line.text.to_string(), Vec::new())),
+                (LogSection::Thermo, Some((_, data_lines))) => {
+                    data_lines.push(line.text.to_string())
+                }
+                (LogSection::End, Some(_)) => return Ok(false),
+                _ => {}
+            }
+            Ok(run.as_ref().is_none_or(|(log_header_str, data_lines)| {
+                !ThermoStyle::detect(log_header_str).has_columns(data_lines)
+            }))
+        })?;
+
+        let Some((log_header_str, data_lines)) = run else {
+            // The run is missing or ended before its header, which is told apart by the run lines
+            let mut error: Box<dyn std::error::Error> =
+                no_thermo_data_error(&self.log_file_name, thermo_run_id);
+            self.for_each_thermo_run(
+                |run_id| run_id == thermo_run_id,
+                |run, _| {
+                    if !run.header_found {
+                        error = missing_header_error(run.header_line, run.log_header_str.clone());
+                    }
+                    Ok(false)
+                },
+            )?;
+            return Err(error);
+        };
+        let schema: Schema = thermo_schema(&log_header_str, data_lines);
+        if schema.is_empty() {
+            return Err(no_thermo_data_error(&self.log_file_name, thermo_run_id));
+        }
+        Ok(schema)
+    }
+
+    /** Memory maps the log file for the mapped parsers, so that it is parsed without
+    copying its lines.
+
+    Returns None unless the reader was created by a mapped parser, and for compressed
+    and empty files, which are streamed instead. */
+    fn mapped_log(&self) -> Result<Option<Mmap>, Box<dyn std::error::Error>> {
+        if !self.mmap {
+            return Ok(None);
+        }
+        let log_file: File = LogLammpsReader::log_file(&self.log_file_name)?;
+        if log_file.metadata()?.len() == 0 {
+            return Ok(None);
+        }
+        // SAFETY: the map is only read, and bytes appended to the file after this point
+        // are not part of it. Truncating the file while it is mapped is undefined
+        // behavior (SIGBUS), which the callers of the `unsafe` mapped parsers rule out
+        // as documented on `LogLammpsReader::parse_mapped_with_report`.
+        let log_map: Mmap = unsafe { Mmap::map(&log_file)? };
+        if Compression::detect(&log_map) != Compression::None {
+            return Ok(None);
+        }
+        Ok(Some(log_map))
+    }
+
+    /// Parses a single thermo run with a projection, returns None if the log file does not have the run
+    fn parse_thermo_run(
+        &self,
+        req_thermo_run_id: u32,
+        projection: &ThermoProjection,
+    ) -> Result<Option<ParsedThermoRun>, Box<dyn std::error::Error>> {
+        let mut parsed: Option<ParsedThermoRun> = None;
+        self.for_each_thermo_run(
+            |thermo_run_id| thermo_run_id == req_thermo_run_id,
+            |run, log_data| {
+                parsed = Some(LogLammpsReader::thermo_to_df(run, log_data, projection)?);
+                Ok(false)
+            },
+        )?;
+        Ok(parsed)
+    }
+
+    /// Method to parse the log file and convert the log file into a DataFrame.
+    fn parse_lammps_log(
+        &self,
+        req_thermo_run_id: u32,
+    ) -> Result<(DataFrame, Vec<SkippedRow>), Box<dyn std::error::Error>> {
+        match self.parse_thermo_run(req_thermo_run_id, &ThermoProjection::default())? {
+            Some(parsed) if parsed.rows > 0 => Ok((parsed.log_data, parsed.skipped_rows)),
+            Some(parsed) => match parsed.skipped_rows.into_iter().next() {
+                Some(first) => Err(Box::new(ThermoParseError::NoValidRows {
+                    line: first.line,
+                    text: first.text,
+                    reason: first.reason,
+                })),
+                None => Err(no_thermo_data_error(&self.log_file_name, req_thermo_run_id)),
+            },
+            None => Err(no_thermo_data_error(&self.log_file_name, req_thermo_run_id)),
+        }
+    }
+
+    /// Method to parse every thermo run of the log file while reading it only once.
+    fn parse_all_lammps_log(&self) -> Result<Vec<DataFrame>, Box<dyn std::error::Error>> {
+        let mut log_data: Vec<DataFrame> = Vec::new();
+        let projection: ThermoProjection = ThermoProjection::default();
+        self.for_each_thermo_run(
+            |_| true,
+            |run, run_data| {
+                log_data.push(without_missing_header(
+                    LogLammpsReader::thermo_to_df(run, run_data, &projection)
+                        .map(|parsed| parsed.log_data),
+                )?);
+                Ok(true)
+            },
+        )?;
+
+        if log_data.iter().all(|df| df.height() == 0) {
+            return Err(Box::new(LammpsError::UnsupportedFormat {
+                path: self.log_file_name.clone(),
+                message: format!(
+                    "No thermo data found in the log file '{}'",
+                    self.log_file_name.display()
+                ),
+            }));
+        }
+
+        Ok(log_data)
+    }
+
+    /// Finds the thermo runs which follow a `reset_timestep` command
+    fn reset_timestep_run_ids(&self) -> Result<Vec<u32>, Box<dyn std::error::Error>> {
+        let mut run_ids: Vec<u32> = Vec::new();
+        self.for_each_line(|line| {
+            if line.section == LogSection::Input
+                && line.text.trim_start().starts_with(RESET_TIMESTEP_FLAG)
+                && run_ids.last() != Some(&line.thermo_run_id)
+            {
+                run_ids.push(line.thermo_run_id);
+            }
+            Ok(true)
+        })?;
+        Ok(run_ids)
+    }
+
+    /// Method to summarize every thermo run of the log file without storing its data.
+    fn list_lammps_log_runs(&self) -> Result<Vec<ThermoRunInfo>, Box<dyn std::error::Error>> {
+        let mut runs: Vec<ThermoRunInfo> = Vec::new();
+        let mut run_kind: ThermoRunKind = ThermoRunKind::Unknown;
+        let mut last_line_number: usize = 0;
+        let mut in_progress: bool = false;
+
+        self.for_each_line(|line| {
+            last_line_number = line.number;
+            match line.section {
+                LogSection::Input => {
+                    if let Some(kind) = ThermoRunKind::from_command(line.text) {
+                        run_kind = kind;
+                    }
+                }
+                LogSection::Header => {
+                    runs.push(ThermoRunInfo::new(
+                        line.thermo_run_id,
+                        run_kind,
+                        line.number,
+                        line.text,
+                    ));
+                    run_kind = ThermoRunKind::Unknown;
+                    in_progress = true;
+                }
+                LogSection::Thermo => {
+                    if let Some(run) = runs.last_mut() {
+                        run.add_line(line.text);
+                    }
+                }
+                LogSection::End => {
+                    if let Some(run) = runs.last_mut() {
+                        run.end_line = line.number;
+                    }
+                    in_progress = false;
+                }
+            }
+            Ok(true)
+        })?;
+
+        if let Some(run) = runs.last_mut().filter(|_| in_progress) {
+            run.end_line = last_line_number;
+        }
+        Ok(runs)
+    }
+
+    /// Method to collect the `WARNING` and `ERROR` messages of the log file.
+    fn parse_log_diagnostics(&self) -> Result<Vec<LogDiagnostic>, Box<dyn std::error::Error>> {
+        let mut diagnostics: Vec<LogDiagnostic> = Vec::new();
+        let mut current_run: Option<ThermoRunInfo> = None;
+        let mut last_step: Option<u64> = None;
+
+        self.for_each_line(|line| {
+            match line.section {
+                LogSection::Header => {
+                    current_run = Some(ThermoRunInfo::new(
+                        line.thermo_run_id,
+                        ThermoRunKind::Unknown,
+                        line.number,
+                        line.text,
+                    ));
+                }
+                LogSection::Thermo => {
+                    if let Some(run) = current_run.as_mut() {
+                        run.add_line(line.text);
+                        last_step = run.last_step.or(last_step);
+                    }
+                }
+                LogSection::Input | LogSection::End => {}
+            }
+
+            if let Some((level, message, location)) = LogDiagnostic::from_line(line.text) {
+                diagnostics.push(LogDiagnostic {
+                    line: line.number,
+                    level,
+                    run_id: (line.section != LogSection::Input).then_some(line.thermo_run_id),
+                    last_step,
+                    message,
+                    location,
+                });
+            }
+            if line.section == LogSection::End {
+                current_run = None;
+            }
+            Ok(true)
+        })?;
+
+        Ok(diagnostics)
+    }
+
+    /** Reads the log file once and calls `on_run` with the lines of every thermo run
+    for which `select_run` is true, along with the lines after its header (see
+    `ThermoRunLines::log_data`).
+
+    The lines of the runs which are not selected are not stored. A run cut off at
+    the end of the file is also passed on. Reading stops as soon as `on_run` returns
+    false. */
+    fn for_each_thermo_run(
+        &self,
+        select_run: impl Fn(u32) -> bool,
+        mut on_run: impl FnMut(&ThermoRunLines, &[u8]) -> Result<bool, Box<dyn std::error::Error>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let log_map: Option<Mmap> = self.mapped_log()?;
+        let log_map: Option<&[u8]> = log_map.as_deref();
+        let mut in_progress: Option<ThermoRunLines> = None;
+        // Set after the `Per MPI rank memory allocation` line until the header is found,
+        // with the first line which is not a header
+        let mut awaiting_header: Option<Option<ThermoRunLines>> = None;
+
+        self.for_each_line_in(log_map, |line, bytes| {
+            if !select_run(line.thermo_run_id) {
+                return Ok(true);
+            }
+            match line.section {
+                LogSection::Input => {
+                    if line.text.starts_with(MPI_FLAG) {
+                        awaiting_header = Some(None);
+                    } else if let Some(rejected @ None) = awaiting_header.as_mut()
+                        && !is_ignored_line(line.text)
+                    {
+                        *rejected = Some(ThermoRunLines::new(line.number, line.text, 0, false));
+                    }
+                    Ok(true)
+                }
+                LogSection::Header => {
+                    awaiting_header = None;
+                    in_progress =
+                        Some(ThermoRunLines::new(line.number, line.text, bytes.end, true));
+                    Ok(true)
+                }
+                LogSection::Thermo => {
+                    if let Some(run) = in_progress.as_mut() {
+                        match log_map {
+                            Some(_) => run.data.end = bytes.end,
+                            None => run.push_line(line.text),
+                        }
+                    }
+                    Ok(true)
+                }
+                LogSection::End => {
+                    let run: Option<ThermoRunLines> = match awaiting_header.take() {
+                        // The run ended before its header, at this line if no other line was found
+                        Some(rejected) => Some(rejected.unwrap_or(ThermoRunLines::new(
+                            line.number,
+                            line.text,
+                            0,
+                            false,
+                        ))),
+                        None => in_progress.take(),
+                    };
+                    match run {
+                        Some(run) => on_run(&run, run.log_data(log_map)),
+                        None => Ok(true),
+                    }
+                }
+            }
         })?;
-        Ok(BufReader::new(log_file))
+
+        if let Some(run) = in_progress.or(awaiting_header.flatten()) {
+            on_run(&run, run.log_data(log_map))?;
+        }
+        Ok(())
     }
 
-    /// Method to parse the log file and convert the log file into a DataFrame.
-    fn parse_lammps_log(
+    /** Streams the log file once and calls `on_footer` with the run id and the lines
+    from the `Loop time` line of each finished run up to the start of the next run. */
+    fn for_each_run_footer(
         &self,
-        req_thermo_run_id: u32,
-    ) -> Result<DataFrame, Box<dyn std::error::Error>> {
-        let mut thermo_run_id: u32 = 0;
-        let mut data_flag: bool = false;
-        let mut log_header_str: String = String::new();
-        let mut raw_log_data: Vec<String> = Vec::new();
-
-        let log_reader: BufReader<File> = LogLammpsReader::log_buffer_reader(&self.log_file_name)?;
+        mut on_footer: impl FnMut(u32, Vec<String>),
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut footer: Option<(u32, Vec<String>)> = None;
 
-        for line_result in log_reader.lines() {
-            let line: String = line_result?;
-
-            if !data_flag {
-                if UNSUPPORTED_THERMO_STYLES
-                    .iter()
-                    .any(|&flag| line.starts_with(flag))
-                {
-                    return Err(format!(
-                        "This thermo style '{}' is not supported. Use 'one' or 'custom'",
-                        line
-                    )
-                    .into());
+        self.for_each_line(|line| {
+            match line.section {
+                LogSection::Input => {
+                    if let Some((_, footer_lines)) = footer.as_mut() {
+                        footer_lines.push(line.text.to_string());
+                    }
                 }
-                if line.starts_with(MPI_FLAG) {
-                    data_flag = true;
+                LogSection::Header => {
+                    if let Some((thermo_run_id, footer_lines)) = footer.take() {
+                        on_footer(thermo_run_id, footer_lines);
+                    }
+                }
+                LogSection::Thermo => {}
+                LogSection::End => {
+                    if line.text.starts_with(ERROR_FLAGS[0]) {
+                        footer = Some((line.thermo_run_id, vec![line.text.to_string()]));
+                    }
                 }
-                continue;
-            }
-            if log_header_str.is_empty() {
-                log_header_str = line;
-                continue;
             }
+            Ok(true)
+        })?;
+
+        if let Some((thermo_run_id, footer_lines)) = footer {
+            on_footer(thermo_run_id, footer_lines);
+        }
+        Ok(())
+    }
+
+    /** Reads the log file once and calls `on_line` for every line together with
+    the thermo run and the section of the log it belongs to.
+
+    Reading stops as soon as `on_line` returns false. */
+    fn for_each_line(
+        &self,
+        mut on_line: impl FnMut(LogLine) -> Result<bool, Box<dyn std::error::Error>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let log_map: Option<Mmap> = self.mapped_log()?;
+        self.for_each_line_in(log_map.as_deref(), |line, _| on_line(line))
+    }
 
-            if ERROR_FLAGS.iter().any(|&flag| line.starts_with(flag)) {
-                data_flag = false;
-                thermo_run_id += 1;
-                if thermo_run_id > req_thermo_run_id {
+    /** Same as `for_each_line` but also passes the byte range of each line, including
+    its line break.
+
+    The lines are taken from `log_map` if the log is memory mapped, otherwise the
+    log file is streamed. Bytes which are not valid UTF-8 are replaced. */
+    fn for_each_line_in(
+        &self,
+        log_map: Option<&[u8]>,
+        mut on_line: impl FnMut(LogLine, Range<usize>) -> Result<bool, Box<dyn std::error::Error>>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let mut scanner: LogScanner = LogScanner::default();
+        let mut position: usize = 0;
+        if let Some(log_map) = log_map {
+            while position < log_map.len() {
+                let end: usize = next_line_start(log_map, position);
+                let line: Cow<str> =
+                    String::from_utf8_lossy(without_line_break(&log_map[position..end]));
+                if !on_line(scanner.scan(&line), position..end)? {
                     break;
                 }
-                log_header_str.clear();
-                continue;
+                position = end;
             }
-            if thermo_run_id != req_thermo_run_id {
-                continue;
+            return Ok(());
+        }
+
+        let mut log_reader: Box<dyn BufRead + Send> =
+            LogLammpsReader::log_buffer_reader(&self.log_file_name)?;
+        let mut bytes: Vec<u8> = Vec::new();
+        loop {
+            bytes.clear();
+            let read: usize = log_reader.read_until(b'\n', &mut bytes)?;
+            if read == 0 {
+                break;
             }
-            raw_log_data.push(line);
+            let line: Cow<str> = String::from_utf8_lossy(without_line_break(&bytes));
+            if !on_line(scanner.scan(&line), position..position + read)? {
+                break;
+            }
+            position += read;
+        }
+        Ok(())
+    }
+
+    /** Converts the lines of a single thermo run into a DataFrame based on its thermo style.
+
+    `log_data` are the lines after the header (see `ThermoRunLines::log_data`). Also
+    returns the rows which were skipped because they are not valid thermo data. */
+    fn thermo_to_df(
+        run: &ThermoRunLines,
+        log_data: &[u8],
+        projection: &ThermoProjection,
+    ) -> Result<ParsedThermoRun, Box<dyn std::error::Error>> {
+        if !run.header_found {
+            return Err(missing_header_error(
+                run.header_line,
+                run.log_header_str.clone(),
+            ));
         }
+        let log_data_str: Cow<str> = String::from_utf8_lossy(log_data);
+        let parsed: (DataFrame, Vec<SkippedRow>) = match ThermoStyle::detect(&run.log_header_str) {
+            ThermoStyle::One => {
+                return LogLammpsReader::one_thermo_to_df(
+                    &run.log_header_str,
+                    log_data,
+                    run.header_line + 1,
+                    projection,
+                );
+            }
+            ThermoStyle::Multi => {
+                let raw_log_data: Vec<&str> = std::iter::once(run.log_header_str.as_str())
+                    .chain(log_data_str.lines())
+                    .collect();
+                LogLammpsReader::multi_thermo_to_df(&raw_log_data, run.header_line)?
+            }
+            ThermoStyle::Yaml => {
+                let raw_log_data: Vec<&str> = log_data_str.lines().collect();
+                LogLammpsReader::yaml_thermo_to_df(&raw_log_data, run.header_line + 1)?
+            }
+        };
+        Ok(ParsedThermoRun::project(parsed, projection)?)
+    }
 
+    /** Converts thermo data printed in columns (`thermo_style one/custom`) into a DataFrame.
+
+    The lines are split into chunks which are parsed in parallel, straight into the
+    column buffers (see `ThermoChunk::parse`), and only the columns and steps of the
+    projection are stored. `first_line` is the line number of the first data line. */
+    fn one_thermo_to_df(
+        log_header_str: &str,
+        log_data: &[u8],
+        first_line: usize,
+        projection: &ThermoProjection,
+    ) -> Result<ParsedThermoRun, Box<dyn std::error::Error>> {
         let log_header: Vec<String> = log_header_str
             .split_whitespace()
             .map(String::from)
             .collect();
+        let keep: Vec<bool> = log_header
+            .iter()
+            .map(|name| projection.keeps_column(name))
+            .collect();
+        let steps: Option<(usize, StepRange)> = log_header
+            .iter()
+            .position(|name| name == STEP_COLUMN)
+            .filter(|_| projection.steps != StepRange::default())
+            .map(|column| (column, projection.steps));
 
-        let log_data: Vec<Vec<f64>> = raw_log_data
-            .into_par_iter()
-            .filter_map(|s| {
-                let row: Vec<f64> = s
-                    .split_whitespace()
-                    .filter_map(|num| num.parse::<f64>().ok())
-                    .collect();
+        let chunks: Vec<Range<usize>> = line_aligned_chunks(log_data);
+        let first_lines: Vec<usize> = chunks
+            .iter()
+            .scan(first_line, |first_line, chunk| {
+                let chunk_first_line: usize = *first_line;
+                *first_line += memchr_iter(b'\n', &log_data[chunk.clone()]).count();
+                Some(chunk_first_line)
+            })
+            .collect();
 
-                if row.len() == log_header.len() {
-                    Some(row)
-                } else {
-                    None
+        let parsed_chunks: Vec<ThermoChunk> = chunks
+            .into_par_iter()
+            .zip(first_lines)
+            .map(|(chunk, first_line)| {
+                let chunk: &[u8] = &log_data[chunk];
+                match std::str::from_utf8(chunk) {
+                    Ok(text) => ThermoChunk::parse(text, &log_header, first_line, &keep, steps),
+                    Err(_) => ThermoChunk::parse(
+                        &String::from_utf8_lossy(chunk),
+                        &log_header,
+                        first_line,
+                        &keep,
+                        steps,
+                    ),
                 }
             })
             .collect();
 
+        let mut column_chunks: Vec<Vec<ColumnBuffer>> = vec![Vec::new(); log_header.len()];
+        let mut rows: usize = 0;
+        let mut skipped_rows: Vec<SkippedRow> = Vec::new();
+        for parsed_chunk in parsed_chunks {
+            for (column, buffer) in column_chunks.iter_mut().zip(parsed_chunk.columns) {
+                column.push(buffer);
+            }
+            rows += parsed_chunk.rows;
+            skipped_rows.extend(parsed_chunk.skipped_rows);
+        }
+        if rows == 0 {
+            return Ok(ParsedThermoRun {
+                log_data: DataFrame::empty(),
+                skipped_rows,
+                rows,
+            });
+        }
+
+        let columns: Vec<Column> = log_header
+            .par_iter()
+            .zip(column_chunks)
+            .zip(keep)
+            .filter(|(_, keep)| *keep)
+            .map(|((name, buffers), _)| ColumnBuffer::concat(name, buffers))
+            .collect::<PolarsResult<Vec<Column>>>()?;
+
+        Ok(ParsedThermoRun {
+            log_data: DataFrame::new(columns)?,
+            skipped_rows,
+            rows,
+        })
+    }
+
+    /** Converts thermo data printed by `thermo_style multi` into a DataFrame.
+
+    Each block starts with a `---- Step N ----- CPU = T (sec) ----` line followed
+    by lines of `Key = value` pairs. The columns are taken from the first block,
+    and blocks which do not have a valid value for every column are skipped.
+    `first_line` is the line number of the first block. */
+    fn multi_thermo_to_df(
+        raw_log_data: &[&str],
+        first_line: usize,
+    ) -> Result<(DataFrame, Vec<SkippedRow>), Box<dyn std::error::Error>> {
+        let mut blocks: Vec<Vec<(String, String)>> = Vec::new();
+        let mut step_lines: Vec<(usize, &str)> = Vec::new();
+        for (index, &line) in raw_log_data.iter().enumerate() {
+            if is_multi_step_line(line) {
+                blocks.push(Vec::new());
+                step_lines.push((first_line + index, line));
+            }
+            if let Some(block) = blocks.last_mut() {
+                block.extend(multi_key_values(line));
+            }
+        }
+
+        let Some(first_block) = blocks.first() else {
+            return Ok((DataFrame::empty(), Vec::new()));
+        };
+        let log_header: Vec<String> = first_block.iter().map(|(key, _)| key.clone()).collect();
+
+        let mut log_data: Vec<Vec<&str>> = Vec::with_capacity(blocks.len());
+        let mut skipped_rows: Vec<SkippedRow> = Vec::new();
+        for (block, (line, step_line)) in blocks.iter().zip(&step_lines) {
+            let row: Result<Vec<&str>, SkipReason> = log_header
+                .iter()
+                .map(|key| {
+                    block
+                        .iter()
+                        .find(|(block_key, _)| block_key == key)
+                        .map(|(_, value)| value.as_str())
+                        .ok_or_else(|| SkipReason::MissingColumn {
+                            column: key.clone(),
+                        })
+                })
+                .collect();
+            match row.and_then(|row| check_row(&row, &log_header).map(|_| row)) {
+                Ok(row) => log_data.push(row),
+                Err(reason) => skipped_rows.push(SkippedRow {
+                    line: *line,
+                    reason,
+                    text: step_line.to_string(),
+                }),
+            }
+        }
+
+        Ok((
+            LogLammpsReader::rows_to_df(&log_header, &log_data)?,
+            skipped_rows,
+        ))
+    }
+
+    /** Converts thermo data printed by `thermo_style yaml` or `thermo_modify line yaml`
+    into a DataFrame.
+
+    The columns are given by the `keywords:` list and each `- [...]` item under `data:`
+    is a row. `first_line` is the line number of the first data line. */
+    fn yaml_thermo_to_df(
+        raw_log_data: &[&str],
+        first_line: usize,
+    ) -> Result<(DataFrame, Vec<SkippedRow>), Box<dyn std::error::Error>> {
+        let mut log_header: Vec<String> = Vec::new();
+        let mut raw_rows: Vec<(usize, &str, Vec<String>)> = Vec::new();
+        for (index, line) in raw_log_data.iter().enumerate() {
+            let line: &str = line.trim();
+            if let Some(keywords) = line.strip_prefix(YAML_KEYWORDS_FLAG) {
+                log_header = yaml_flow_sequence(keywords);
+            } else if line.starts_with(YAML_ROW_FLAG) {
+                raw_rows.push((first_line + index, line, yaml_flow_sequence(&line[1..])));
+            }
+        }
+
+        let mut log_data: Vec<Vec<&str>> = Vec::with_capacity(raw_rows.len());
+        let mut skipped_rows: Vec<SkippedRow> = Vec::new();
+        for (line, text, row) in &raw_rows {
+            let row: Vec<&str> = row.iter().map(String::as_str).collect();
+            match check_row(&row, &log_header) {
+                Ok(()) => log_data.push(row),
+                Err(reason) => skipped_rows.push(SkippedRow {
+                    line: *line,
+                    reason,
+                    text: text.to_string(),
+                }),
+            }
+        }
+
+        Ok((
+            LogLammpsReader::rows_to_df(&log_header, &log_data)?,
+            skipped_rows,
+        ))
+    }
+
+    /** Convert the parsed rows into a DataFrame with one column for each header.
+
+    Columns where every value is an integer are stored as i64, otherwise f64. */
+    fn rows_to_df(
+        log_header: &[String],
+        log_data: &[Vec<&str>],
+    ) -> Result<DataFrame, Box<dyn std::error::Error>> {
         if log_data.is_empty() {
-            return Err(format!(
-                "No data found in the log file for run: {}\nThis may be caused due to:
-                \n1. Incorrect 'requried_thermo_run_id' parameter (Try 'requried_thermo_run_id = {}')
-                \n2. Unsual format of log file",
-                req_thermo_run_id,
-                req_thermo_run_id.saturating_sub(1)
-            )
-            .into());
+            return Ok(DataFrame::empty());
         }
 
         // Convert the parsed data into a polars Series
-        let columns: Vec<Column> = (0..log_header.len())
-            .map(|index: usize| {
-                let column_data: Vec<f64> = log_data
-                    .par_iter()
-                    .map(|row: &Vec<f64>| row[index])
-                    .collect();
-                Column::new((&log_header[index]).into(), column_data)
+        let columns: Vec<Column> = log_header
+            .par_iter()
+            .enumerate()
+            .map(|(index, name)| {
+                let column_data: Vec<&str> = log_data.iter().map(|row| row[index]).collect();
+                infer_column(name, &column_data)
             })
             .collect();
 
         Ok(DataFrame::new(columns)?)
     }
 
+    /// Casts the columns of a DataFrame to the data types given in the schema
+    pub fn apply_schema(
+        mut log_data: DataFrame,
+        schema: &Schema,
+    ) -> Result<DataFrame, Box<dyn std::error::Error>> {
+        for (name, dtype) in schema.iter() {
+            if !log_data.get_column_names().contains(&name) {
+                return Err(format!(
+                    "Column '{}' in the schema was not found in the thermo data. Available columns: {:?}",
+                    name,
+                    log_data.get_column_names()
+                )
+                .into());
+            }
+            log_data.try_apply(name.as_str(), |column| column.cast(dtype))?;
+        }
+        Ok(log_data)
+    }
+
     /// Returns all instance of a prefix string in a file
     fn parse_log_starts_with(
         &self,
         prefix_key: &str,
     ) -> Result<Vec<String>, Box<dyn std::error::Error>> {
         let mut matched_lines: Vec<String> = Vec::new();
-        let log_reader: BufReader<File> = LogLammpsReader::log_buffer_reader(&self.log_file_name)?;
+        let log_reader: Box<dyn BufRead + Send> =
+            LogLammpsReader::log_buffer_reader(&self.log_file_name)?;
         for line_result in log_reader.lines() {
             let line: String = line_result?;
             if line.trim().starts_with(prefix_key) {
@@ -172,3 +2356,1044 @@ impl LogLammpsReader {
         Ok(matched_lines)
     }
 }
+
+/// New thermo rows of a single thermo run read by `LogTail::poll`
+#[derive(Clone, Debug)]
+pub struct ThermoUpdate {
+    pub run_id: u32,
+    /// The header of the run was read in this poll
+    pub new_run: bool,
+    /// The run ended at `Loop time` or `ERROR` in this poll
+    pub finished: bool,
+    pub rows: DataFrame,
+}
+
+/// The thermo run which is currently being read by `LogTail`
+struct TailRun {
+    run_id: u32,
+    header: String,
+    style: ThermoStyle,
+    /// The `keywords:` line of `thermo_style yaml`, needed to convert every later row
+    yaml_keywords: Option<String>,
+    /// Line number of the first line in `lines`
+    first_line: usize,
+    /// Lines which have not been converted into rows yet
+    lines: Vec<String>,
+}
+
+impl TailRun {
+    /** Converts the lines read so far into rows.
+
+    The last `thermo_style multi` block is kept until the run ends, as more of its
+    lines might still be written. */
+    fn take_rows(&mut self, run_ended: bool) -> Result<DataFrame, Box<dyn std::error::Error>> {
+        let split_at: usize = match self.style {
+            ThermoStyle::Multi if !run_ended => self
+                .lines
+                .iter()
+                .rposition(|line| is_multi_step_line(line))
+                .unwrap_or(0),
+            _ => self.lines.len(),
+        };
+        let mut lines: Vec<String> = self.lines.drain(..split_at).collect();
+        let first_line: usize = self.first_line;
+        self.first_line += lines.len();
+        if lines.is_empty() {
+            return Ok(DataFrame::empty());
+        }
+
+        let (header_line, log_header_str, raw_log_data): (usize, String, Vec<String>) = match self
+            .style
+        {
+            ThermoStyle::One => (first_line - 1, self.header.clone(), lines),
+            ThermoStyle::Multi => {
+                let log_header_str: String = lines.remove(0);
+                (first_line, log_header_str, lines)
+            }
+            ThermoStyle::Yaml => {
+                let mut raw_log_data: Vec<String> = self.yaml_keywords.iter().cloned().collect();
+                let header_line: usize = first_line - 1 - raw_log_data.len();
+                raw_log_data.extend(lines);
+                (header_line, self.header.clone(), raw_log_data)
+            }
+        };
+        let mut run: ThermoRunLines = ThermoRunLines::new(header_line, &log_header_str, 0, true);
+        for line in &raw_log_data {
+            run.push_line(line);
+        }
+        let projection: ThermoProjection = ThermoProjection::default();
+        Ok(LogLammpsReader::thermo_to_df(&run, run.log_data(None), &projection)?.log_data)
+    }
+}
+
+/** Follows a log file which is still being written.
+
+Each `poll` reads the file from the byte offset where the previous one stopped, so
+only the new thermo rows are parsed. An incomplete last line is kept until the rest
+of it is written. Unlike the other readers, compressed files are not supported. */
+pub struct LogTail {
+    reader: LogLammpsReader,
+    scanner: LogScanner,
+    /// Number of bytes of the file read so far
+    offset: u64,
+    /// Bytes after the last newline which was read
+    partial_line: Vec<u8>,
+    current_run: Option<TailRun>,
+    run_id: Option<u32>,
+    run_data: DataFrame,
+}
+
+impl LogTail {
+    /** Reads the lines written to the log file since the last poll.
+
+    Returns the new rows of every thermo run which was started, continued or
+    finished since the last poll. The new rows are also appended to `run_data`.
+    If the file became shorter than what was read before, it is read again from
+    the beginning. */
+    pub fn poll(&mut self) -> Result<Vec<ThermoUpdate>, LammpsError> {
+        self.read_updates().map_err(|e| self.reader.lammps_error(e))
+    }
+
+    /// Reads the new lines of `poll`
+    fn read_updates(&mut self) -> Result<Vec<ThermoUpdate>, Box<dyn std::error::Error>> {
+        let mut log_file: File = LogLammpsReader::log_file(&self.reader.log_file_name)?;
+        if log_file.metadata()?.len() < self.offset {
+            *self = LogLammpsReader::tail(self.reader.log_file_name.clone());
+        }
+        log_file.seek(SeekFrom::Start(self.offset))?;
+
+        let mut bytes: Vec<u8> = std::mem::take(&mut self.partial_line);
+        let read: usize = log_file.read_to_end(&mut bytes)?;
+        self.offset += read as u64;
+        let complete: usize = bytes
+            .iter()
+            .rposition(|&byte| byte == b'\n')
+            .map_or(0, |index| index + 1);
+        self.partial_line = bytes.split_off(complete);
+
+        let mut updates: Vec<ThermoUpdate> = Vec::new();
+        for line in String::from_utf8_lossy(&bytes).lines() {
+            self.read_line(line, &mut updates)?;
+        }
+        if let Some(mut run) = self.current_run.take() {
+            let rows: DataFrame = run.take_rows(false)?;
+            self.add_rows(&mut updates, run.run_id, rows, false)?;
+            self.current_run = Some(run);
+        }
+        Ok(updates)
+    }
+
+    /// The id of the thermo run in `run_data`, None if no run was read yet
+    pub fn run_id(&self) -> Option<u32> {
+        self.run_id
+    }
+
+    /** All thermo rows of the current (or the last finished) run read so far.
+
+    Each poll appends its rows as new chunks, which are only joined here. */
+    pub fn run_data(&mut self) -> &DataFrame {
+        self.run_data.as_single_chunk_par();
+        &self.run_data
+    }
+
+    /// Updates the current run with a complete line of the log file
+    fn read_line(
+        &mut self,
+        line: &str,
+        updates: &mut Vec<ThermoUpdate>,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        let line: LogLine = self.scanner.scan(line);
+        match line.section {
+            LogSection::Input => {}
+            LogSection::Header => {
+                let style: ThermoStyle = ThermoStyle::detect(line.text);
+                let mut run = TailRun {
+                    run_id: line.thermo_run_id,
+                    header: line.text.to_string(),
+                    style,
+                    yaml_keywords: None,
+                    first_line: line.number + 1,
+                    lines: Vec::new(),
+                };
+                if style == ThermoStyle::Multi {
+                    run.first_line = line.number;
+                    run.lines.push(line.text.to_string());
+                }
+                self.current_run = Some(run);
+                self.run_id = Some(line.thermo_run_id);
+                self.run_data = DataFrame::empty();
+                updates.push(ThermoUpdate {
+                    run_id: line.thermo_run_id,
+                    new_run: true,
+                    finished: false,
+                    rows: DataFrame::empty(),
+                });
+            }
+            LogSection::Thermo => {
+                if let Some(run) = self.current_run.as_mut() {
+                    if run.style == ThermoStyle::Yaml
+                        && line.text.trim().starts_with(YAML_KEYWORDS_FLAG)
+                    {
+                        run.yaml_keywords = Some(line.text.to_string());
+                        run.first_line += 1;
+                    } else {
+                        run.lines.push(line.text.to_string());
+                    }
+                }
+            }
+            LogSection::End => {
+                if let Some(mut run) = self.current_run.take() {
+                    let rows: DataFrame = run.take_rows(true)?;
+                    self.add_rows(updates, run.run_id, rows, true)?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Appends new rows to `run_data` and to the update of the run
+    fn add_rows(
+        &mut self,
+        updates: &mut Vec<ThermoUpdate>,
+        run_id: u32,
+        rows: DataFrame,
+        finished: bool,
+    ) -> Result<(), Box<dyn std::error::Error>> {
+        if rows.height() == 0 && !finished {
+            return Ok(());
+        }
+        append_rows(&mut self.run_data, &rows)?;
+        match updates.last_mut().filter(|update| update.run_id == run_id) {
+            Some(update) => {
+                append_rows(&mut update.rows, &rows)?;
+                update.finished = finished;
+            }
+            None => updates.push(ThermoUpdate {
+                run_id,
+                new_run: false,
+                finished,
+                rows,
+            }),
+        }
+        Ok(())
+    }
+}
+
+/** Appends rows to a DataFrame, casting columns to a common data type if needed.
+
+Rows with the same columns and data types are added as new chunks, so the rows
+which were already read are not copied. */
+fn append_rows(log_data: &mut DataFrame, rows: &DataFrame) -> PolarsResult<()> {
+    if rows.height() == 0 {
+        return Ok(());
+    }
+    if log_data.height() == 0 {
+        *log_data = rows.clone();
+        return Ok(());
+    }
+    if log_data.get_column_names() == rows.get_column_names() && log_data.dtypes() == rows.dtypes()
+    {
+        log_data.vstack_mut(rows)?;
+        return Ok(());
+    }
+    *log_data = concat_lf_diagonal(
+        [std::mem::take(log_data).lazy(), rows.clone().lazy()],
+        UnionArgs {
+            to_supertypes: true,
+            ..Default::default()
+        },
+    )?
+    .collect()?;
+    Ok(())
+}
+
+/// Checks if a line is the `---- Step N ----- CPU = ...` line which starts a `thermo_style multi` block
+fn is_multi_step_line(line: &str) -> bool {
+    line.starts_with('-') && line.contains(MULTI_STEP_FLAG)
+}
+
+/** Returns the `Key = value` pairs in a line of `thermo_style multi` output.
+
+The `---- Step N ----- CPU = T (sec) ----` line gives the `Step` and `CPU` pairs. */
+fn multi_key_values(line: &str) -> Vec<(String, String)> {
+    let tokens: Vec<&str> = line.split_whitespace().collect();
+    let mut key_values: Vec<(String, String)> = Vec::new();
+    let mut index: usize = 0;
+    while index + 1 < tokens.len() {
+        if tokens[index] == "Step" && is_multi_step_line(line) {
+            key_values.push(("Step".to_string(), tokens[index + 1].to_string()));
+            index += 2;
+        } else if index + 2 < tokens.len() && tokens[index + 1] == "=" {
+            key_values.push((tokens[index].to_string(), tokens[index + 2].to_string()));
+            index += 3;
+        } else {
+            index += 1;
+        }
+    }
+    key_values
+}
+
+/// Returns the items in a YAML flow sequence such as `['Step', 'Temp', ]` or `[0, 3.1, ]`
+fn yaml_flow_sequence(sequence: &str) -> Vec<String> {
+    sequence
+        .trim()
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .split(',')
+        .map(|item| item.trim().trim_matches(|c| c == '\'' || c == '"'))
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Returns a line of the log file without its line break
+fn without_line_break(line: &[u8]) -> &[u8] {
+    let line: &[u8] = line.strip_suffix(b"\n").unwrap_or(line);
+    line.strip_suffix(b"\r").unwrap_or(line)
+}
+
+/// Returns the start of the line after the one at `position`, or the end of the file
+fn next_line_start(log_map: &[u8], position: usize) -> usize {
+    memchr(b'\n', &log_map[position..]).map_or(log_map.len(), |index| position + index + 1)
+}
+
+/** Splits the data of a thermo run into chunks which end at a newline.
+
+There are a few chunks for every thread so that uneven chunks still keep every thread
+busy, but each chunk is at least `MIN_CHUNK_SIZE` bytes. */
+fn line_aligned_chunks(log_data: &[u8]) -> Vec<Range<usize>> {
+    let chunk_count: usize = (rayon::current_num_threads() * 4)
+        .min(log_data.len() / MIN_CHUNK_SIZE)
+        .max(1);
+    let chunk_size: usize = log_data.len().div_ceil(chunk_count);
+
+    let mut chunks: Vec<Range<usize>> = Vec::with_capacity(chunk_count);
+    let mut start: usize = 0;
+    while start < log_data.len() {
+        let end: usize = next_line_start(log_data, (start + chunk_size).min(log_data.len()) - 1);
+        chunks.push(start..end);
+        start = end;
+    }
+    chunks
+}
+
+/** Returns the schema of a thermo run from its header and its first data lines (see
+`ThermoStyle::has_columns`), without reading the rows.
+
+The columns of integer thermo keywords such as `Step` are Int64 and the other
+columns are Float64. */
+fn thermo_schema<S: AsRef<str>>(
+    log_header_str: &str,
+    data_lines: impl IntoIterator<Item = S>,
+) -> Schema {
+    let mut data_lines = data_lines.into_iter();
+    let columns: Vec<String> = match ThermoStyle::detect(log_header_str) {
+        ThermoStyle::One => log_header_str
+            .split_whitespace()
+            .map(String::from)
+            .collect(),
+        ThermoStyle::Multi => {
+            let mut key_values: Vec<(String, String)> = multi_key_values(log_header_str);
+            for line in data_lines {
+                if is_multi_step_line(line.as_ref()) {
+                    break;
+                }
+                key_values.extend(multi_key_values(line.as_ref()));
+            }
+            key_values.into_iter().map(|(key, _)| key).collect()
+        }
+        ThermoStyle::Yaml => data_lines
+            .find_map(|line| {
+                line.as_ref()
+                    .trim()
+                    .strip_prefix(YAML_KEYWORDS_FLAG)
+                    .map(yaml_flow_sequence)
+            })
+            .unwrap_or_default(),
+    };
+    columns
+        .into_iter()
+        .map(|name| {
+            let dtype: DataType = if THERMO_INTEGER_KEYWORDS.contains(&name.as_str()) {
+                DataType::Int64
+            } else {
+                DataType::Float64
+            };
+            Field::new(name.into(), dtype)
+        })
+        .collect()
+}
+
+/// Error for a thermo run which ended before its header, `text` is the first line which is not a header
+fn missing_header_error(line: usize, text: String) -> Box<dyn std::error::Error> {
+    Box::new(ThermoParseError::MissingHeader { line, text })
+}
+
+/// Error for a thermo run which is missing or has no valid rows
+fn no_thermo_data_error(
+    log_file_name: &Path,
+    req_thermo_run_id: u32,
+) -> Box<dyn std::error::Error> {
+    Box::new(LammpsError::RunNotFound {
+        path: log_file_name.to_path_buf(),
+        run_id: req_thermo_run_id,
+        message: format!(
+            "No data found in the log file for run: {}\nThis may be caused due to:
+                \n1. Incorrect 'requried_thermo_run_id' parameter (Try 'requried_thermo_run_id = {}')
+                \n2. Unsual format of log file",
+            req_thermo_run_id,
+            req_thermo_run_id.saturating_sub(1)
+        ),
+    })
+}
+
+/// Joins the thermo runs of `parse_all` into one DataFrame with a `run_id` column
+fn concat_runs(log_data: Vec<DataFrame>) -> PolarsResult<DataFrame> {
+    let log_data: Vec<LazyFrame> = log_data
+        .into_iter()
+        .enumerate()
+        .filter(|(_, df)| df.height() > 0)
+        .map(|(thermo_run_id, mut df)| {
+            let run_id: Column =
+                Column::new("run_id".into(), vec![thermo_run_id as u32; df.height()]);
+            df.insert_column(0, run_id)?;
+            Ok(df.lazy())
+        })
+        .collect::<PolarsResult<Vec<LazyFrame>>>()?;
+
+    concat_lf_diagonal(
+        log_data,
+        UnionArgs {
+            to_supertypes: true,
+            ..Default::default()
+        },
+    )?
+    .collect()
+}
+
+/// Checks if a line inside a thermo run is not meant to be a row, such as a blank line or a warning
+fn is_ignored_line(line: &str) -> bool {
+    let line: &str = line.trim_start();
+    line.is_empty() || line.starts_with(WARNING_FLAG)
+}
+
+/// Replaces the error of a thermo run without a header by an empty DataFrame
+fn without_missing_header(
+    log_data: Result<DataFrame, Box<dyn std::error::Error>>,
+) -> Result<DataFrame, Box<dyn std::error::Error>> {
+    match log_data {
+        Err(e)
+            if matches!(
+                e.downcast_ref::<ThermoParseError>(),
+                Some(ThermoParseError::MissingHeader { .. })
+            ) =>
+        {
+            Ok(DataFrame::empty())
+        }
+        log_data => log_data,
+    }
+}
+
+/** Checks if a line is the header of a thermo run.
+
+`multi` and `yaml` runs start with their own markers. Otherwise none of the
+columns may be a number, and at least one must be a thermo keyword or the column
+of a compute, fix or variable, so that columns renamed by `thermo_modify colname`
+are still accepted. */
+fn is_thermo_header(line: &str) -> bool {
+    if is_ignored_line(line) {
+        return false;
+    }
+    if ThermoStyle::detect(line) != ThermoStyle::One {
+        return true;
+    }
+    let columns: Vec<&str> = line.split_whitespace().collect();
+    !columns.is_empty()
+        && columns
+            .iter()
+            .all(|column| parse_thermo_value(column).is_none())
+        && columns.iter().any(|column| {
+            THERMO_KEYWORDS.contains(column)
+                || THERMO_REFERENCE_PREFIXES
+                    .iter()
+                    .any(|prefix| column.starts_with(prefix))
+        })
+}
+
+/// Checks if a row has a value for every column and every value is a number
+fn check_row<S: AsRef<str>>(row: &[S], columns: &[String]) -> Result<(), SkipReason> {
+    if row.len() != columns.len() {
+        return Err(SkipReason::ColumnCount {
+            expected: columns.len(),
+            found: row.len(),
+        });
+    }
+    match row
+        .iter()
+        .zip(columns)
+        .find(|(value, _)| parse_thermo_value(value.as_ref()).is_none())
+    {
+        Some((value, column)) => Err(SkipReason::InvalidValue {
+            column: column.clone(),
+            value: value.as_ref().to_string(),
+        }),
+        None => Ok(()),
+    }
+}
+
+/** Parses a thermo value, including the spellings of `nan` and `inf` which are
+not understood by `str::parse`.
+
+These are `-nan(ind)` and `nan(0x...)` from glibc and MSVC, `1.#QNAN`, `1.#IND`
+and `1.#INF` from older MSVC runtimes and `.nan` and `.inf` from YAML output. */
+fn parse_thermo_value(value: &str) -> Option<f64> {
+    if let Ok(number) = value.parse::<f64>() {
+        return Some(number);
+    }
+    let (negative, unsigned): (bool, &str) = match value.strip_prefix('-') {
+        Some(unsigned) => (true, unsigned),
+        None => (false, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let unsigned: String = unsigned.to_ascii_lowercase();
+    let infinity: f64 = if negative {
+        f64::NEG_INFINITY
+    } else {
+        f64::INFINITY
+    };
+    if unsigned.starts_with("nan(") || unsigned == ".nan" {
+        Some(f64::NAN)
+    } else if unsigned == ".inf" {
+        Some(infinity)
+    } else if let Some((_, special)) = unsigned.split_once(".#") {
+        if special.starts_with("qnan") || special.starts_with("snan") || special.starts_with("ind")
+        {
+            Some(f64::NAN)
+        } else if special.starts_with("inf") {
+            Some(infinity)
+        } else {
+            None
+        }
+    } else {
+        None
+    }
+}
+
+/// Creates an i64 column if every value is an integer, otherwise an f64 column
+fn infer_column(name: &str, values: &[&str]) -> Column {
+    if values.iter().all(|v| v.parse::<i64>().is_ok()) {
+        let col_values: Vec<i64> = values.iter().map(|v| v.parse().unwrap()).collect();
+        Column::new(name.into(), col_values)
+    } else {
+        let col_values: Vec<f64> = values
+            .iter()
+            .map(|v| parse_thermo_value(v).unwrap_or(f64::NAN))
+            .collect();
+        Column::new(name.into(), col_values)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::Write as _;
+    use std::io::Write as _;
+
+    const MPI_LINE: &str = "Per MPI rank memory allocation (min/avg/max) = 3.3 | 3.3 | 3.3 Mbytes";
+    const LOOP_LINE: &str = "Loop time of 0.0258 on 1 procs for 100 steps with 4000 atoms";
+
+    /// Writes a log file and a gzip copy of it
+    fn write_logs(name: &str, log: &str) -> (PathBuf, PathBuf) {
+        let log_file_name: PathBuf =
+            std::env::temp_dir().join(format!("log_lammps_reader_{}_{}", std::process::id(), name));
+        std::fs::write(&log_file_name, log).unwrap();
+        let gz_file_name: PathBuf = log_file_name.with_extension("gz");
+        let mut encoder = flate2::write::GzEncoder::new(
+            File::create(&gz_file_name).unwrap(),
+            flate2::Compression::default(),
+        );
+        encoder.write_all(log.as_bytes()).unwrap();
+        encoder.finish().unwrap();
+        (log_file_name, gz_file_name)
+    }
+
+    /// Parses a thermo run of the log file streamed, memory mapped and from its gzip copy, which must agree
+    fn parse_both(name: &str, log: &str, thermo_run_id: u32) -> (DataFrame, Vec<SkippedRow>) {
+        let (log_file_name, gz_file_name) = write_logs(name, log);
+        let (streamed, streamed_skipped) =
+            LogLammpsReader::parse_with_report(log_file_name.clone(), Some(thermo_run_id)).unwrap();
+        // SAFETY: the test file is not written to while it is parsed
+        let (mapped, mapped_skipped) = unsafe {
+            LogLammpsReader::parse_mapped_with_report(log_file_name, Some(thermo_run_id)).unwrap()
+        };
+        let (gz, gz_skipped) =
+            LogLammpsReader::parse_with_report(gz_file_name, Some(thermo_run_id)).unwrap();
+        assert!(mapped.equals_missing(&streamed), "{mapped}\n{streamed}");
+        assert!(gz.equals_missing(&streamed), "{gz}\n{streamed}");
+        let lines = |skipped: &[SkippedRow]| -> Vec<(usize, String)> {
+            skipped
+                .iter()
+                .map(|row| (row.line, row.text.clone()))
+                .collect()
+        };
+        assert_eq!(lines(&mapped_skipped), lines(&streamed_skipped));
+        assert_eq!(lines(&gz_skipped), lines(&streamed_skipped));
+        (streamed, streamed_skipped)
+    }
+
+    #[test]
+    fn mapped_chunks_match_streamed_parse() {
+        // Several MIN_CHUNK_SIZE chunks, with a warning and a short row in between
+        let mut log: String = format!("LAMMPS (2 Aug 2023)\nrun 100000\n{MPI_LINE}\n");
+        log.push_str("   Step          Temp          PotEng         Atoms   \n");
+        for step in 0..100_000 {
+            if step == 40_000 {
+                log.push_str("WARNING: Lost atoms: original 4000 current 3999\n");
+            }
+            if step == 70_000 {
+                log.push_str("   70000   1.2\n");
+            }
+            let temp: f64 = 1.0 + step as f64 * 1e-5;
+            writeln!(log, "{step:>8} {temp:>14.8} {:>14.8} {:>8}", -temp, 4000).unwrap();
+        }
+        writeln!(log, "{LOOP_LINE}").unwrap();
+        assert!(log.len() > 4 * MIN_CHUNK_SIZE);
+
+        let (log_data, skipped_rows) = parse_both("chunks", &log, 0);
+        assert_eq!(log_data.shape(), (100_000, 4));
+        assert_eq!(log_data.column("Step").unwrap().dtype(), &DataType::Int64);
+        assert_eq!(log_data.column("Temp").unwrap().dtype(), &DataType::Float64);
+        assert_eq!(log_data.column("Atoms").unwrap().dtype(), &DataType::Int64);
+        let steps = log_data.column("Step").unwrap().i64().unwrap();
+        assert!(steps.into_no_null_iter().eq(0..100_000));
+        assert_eq!(skipped_rows.len(), 1);
+        assert_eq!(skipped_rows[0].line, 70_006);
+        assert_eq!(
+            skipped_rows[0].reason,
+            SkipReason::ColumnCount {
+                expected: 4,
+                found: 2
+            }
+        );
+    }
+
+    #[test]
+    fn parses_multi_runs() {
+        let log: String = format!(
+            "LAMMPS (2 Aug 2023)\nthermo_style multi\nrun 100\n{MPI_LINE}
+------------ Step              0 ----- CPU =            0 (sec) -------------
+TotEng   =        -6.7733 KinEng   =         4.6250 Temp     =         3.0000
+Press    =        -5.0162 Volume   =       238.2637
+------------ Step             50 ----- CPU =   0.01242 (sec) -------------
+TotEng   =        -6.7724 KinEng   =         2.2300 Temp     =         1.4465
+Press    =         1.1345
+------------ Step            100 ----- CPU =      0.0258 (sec) -------------
+TotEng   =        -6.7718 KinEng   =         2.4000 Temp     =         1.6000
+Press    =         0.5000 Volume   =       238.2637
+{LOOP_LINE}\n"
+        );
+        let (log_data, skipped_rows) = parse_both("multi", &log, 0);
+        let columns: Vec<&str> = log_data
+            .get_column_names()
+            .into_iter()
+            .map(|name| name.as_str())
+            .collect();
+        assert_eq!(
+            columns,
+            ["Step", "CPU", "TotEng", "KinEng", "Temp", "Press", "Volume"]
+        );
+        let steps = log_data.column("Step").unwrap().i64().unwrap();
+        assert!(steps.into_no_null_iter().eq([0, 100]));
+        let temp = log_data.column("Temp").unwrap().f64().unwrap();
+        assert!(temp.into_no_null_iter().eq([3.0, 1.6]));
+        // The block without `Volume` is skipped
+        assert_eq!(skipped_rows.len(), 1);
+        assert_eq!(skipped_rows[0].line, 8);
+    }
+
+    #[test]
+    fn parses_yaml_runs() {
+        let log: String = format!(
+            "LAMMPS (2 Aug 2023)\nthermo_style yaml\nrun 100\n{MPI_LINE}
+---
+keywords: ['Step', 'Temp', 'E_pair', 'c_msd[4]', ]
+data:
+  - [0, 3, -6.77336805325924, 0, ]
+  - [50, 1.66657731, -4.8, 0.25, ]
+  - [100, 1.69, -4.84, .nan, ]
+...
+{LOOP_LINE}
+
+thermo_modify line yaml
+run 50
+{MPI_LINE}
+---
+keywords: ['Step', 'Temp', ]
+data:
+  - [100, 1.69, ]
+  - [150, 1.7, ]
+...
+{LOOP_LINE}\n"
+        );
+        let (log_data, skipped_rows) = parse_both("yaml", &log, 0);
+        assert!(skipped_rows.is_empty());
+        assert_eq!(log_data.shape(), (3, 4));
+        let msd = log_data.column("c_msd[4]").unwrap().f64().unwrap();
+        assert_eq!(msd.get(1), Some(0.25));
+        assert!(msd.get(2).unwrap().is_nan());
+
+        let (log_data, _) = parse_both("yaml", &log, 1);
+        assert_eq!(log_data.shape(), (2, 2));
+        let steps = log_data.column("Step").unwrap().i64().unwrap();
+        assert!(steps.into_no_null_iter().eq([100, 150]));
+    }
+
+    #[test]
+    fn keeps_nan_and_inf_values() {
+        let log: String = format!(
+            "LAMMPS (2 Aug 2023)\nrun 400\n{MPI_LINE}
+   Step          Temp          Press     
+         0   1.5            -nan          
+       100   inf            1.#QNAN   
+       200   -inf           nan(0x8000000000000)
+       300   -1.#INF        -nan(ind)
+       400   1.5            none
+{LOOP_LINE}\n"
+        );
+        let (log_data, skipped_rows) = parse_both("nan", &log, 0);
+        assert_eq!(log_data.height(), 4);
+        let temp = log_data.column("Temp").unwrap().f64().unwrap();
+        assert!(temp.into_no_null_iter().eq([
+            1.5,
+            f64::INFINITY,
+            f64::NEG_INFINITY,
+            f64::NEG_INFINITY
+        ]));
+        let press = log_data.column("Press").unwrap().f64().unwrap();
+        assert!(press.into_no_null_iter().all(f64::is_nan));
+        assert_eq!(skipped_rows.len(), 1);
+        assert_eq!(
+            skipped_rows[0].reason,
+            SkipReason::InvalidValue {
+                column: "Press".to_string(),
+                value: "none".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn detects_thermo_headers() {
+        assert!(is_thermo_header("   Step          Temp          E_pair  "));
+        assert!(is_thermo_header(
+            "Step Ndanger Cella Cellb Cellc CellAlpha CellBeta CellGamma"
+        ));
+        assert!(is_thermo_header("Step Diheds Impros T/CPU CPULeft"));
+        // Columns renamed by `thermo_modify colname` next to a compute or a variable
+        assert!(is_thermo_header("Time_ps c_myTemp[1] v_press d_charge"));
+        assert!(is_thermo_header(
+            "------------ Step              0 ----- CPU =            0 (sec) -------------"
+        ));
+        assert!(is_thermo_header("---"));
+        assert!(!is_thermo_header("       100   1.69   -4.84"));
+        assert!(!is_thermo_header("Step 100 Temp"));
+        assert!(!is_thermo_header("Neighbor list info ..."));
+        assert!(!is_thermo_header("WARNING: Step Temp"));
+        assert!(!is_thermo_header("  "));
+    }
+
+    #[test]
+    fn splits_runs_when_thermo_style_changes() {
+        let log: String = format!(
+            "LAMMPS (2 Aug 2023)\nrun 100\n{MPI_LINE}
+   Step          Temp     
+         0   3            
+       100   1.69         
+{LOOP_LINE}
+
+thermo_style custom step pe ndanger cella
+run 100
+{MPI_LINE}
+WARNING: Dangerous builds
+   Step          PotEng        Ndanger        Cella     
+       100  -4.84           0              16.79        
+       200  -4.9            2              16.79        
+{LOOP_LINE}
+
+thermo_style multi
+run 100
+{MPI_LINE}
+------------ Step            200 ----- CPU =            0 (sec) -------------
+TotEng   =        -6.7733 Temp     =         3.0000
+{LOOP_LINE}\n"
+        );
+        let columns = |log_data: &DataFrame| -> Vec<String> {
+            log_data
+                .get_column_names()
+                .into_iter()
+                .map(|name| name.to_string())
+                .collect()
+        };
+        let (first_run, _) = parse_both("styles", &log, 0);
+        assert_eq!(columns(&first_run), ["Step", "Temp"]);
+        let (second_run, _) = parse_both("styles", &log, 1);
+        assert_eq!(columns(&second_run), ["Step", "PotEng", "Ndanger", "Cella"]);
+        assert_eq!(second_run.height(), 2);
+        let (third_run, _) = parse_both("styles", &log, 2);
+        assert_eq!(columns(&third_run), ["Step", "CPU", "TotEng", "Temp"]);
+
+        let (log_file_name, _) = write_logs("styles", &log);
+        let runs: Vec<DataFrame> = LogLammpsReader::parse_all(log_file_name.clone()).unwrap();
+        assert_eq!(runs.len(), 3);
+        assert!(runs[1].equals_missing(&second_run));
+        // SAFETY: the test file is not written to while it is parsed
+        let mapped_runs: Vec<DataFrame> =
+            unsafe { LogLammpsReader::parse_all_mapped(log_file_name).unwrap() };
+        assert_eq!(runs, mapped_runs);
+    }
+
+    #[test]
+    fn finds_step_range_of_filters() {
+        let step = || col(STEP_COLUMN);
+        let range = |start: Option<i64>, stop: Option<i64>| StepRange { start, stop };
+        assert_eq!(
+            StepRange::from_predicate(&step().gt_eq(lit(1000))),
+            range(Some(1000), None)
+        );
+        assert_eq!(
+            StepRange::from_predicate(&step().gt(lit(100.5))),
+            range(Some(101), None)
+        );
+        assert_eq!(
+            StepRange::from_predicate(&lit(5000).gt(step()).and(step().gt(lit(1000)))),
+            range(Some(1000), Some(5000))
+        );
+        // Integer bounds beyond the precision of f64 are kept as they are
+        assert_eq!(
+            StepRange::from_predicate(&step().lt_eq(lit(9_007_199_254_740_993_i64))),
+            range(None, Some(9_007_199_254_740_993))
+        );
+        let empty: StepRange = StepRange::from_predicate(&step().eq(lit(10.5)));
+        assert!(!empty.contains(10) && !empty.contains(11));
+        // Filters on other columns or other operators keep every step
+        assert_eq!(
+            StepRange::from_predicate(&col("Temp").gt(lit(1.0))),
+            StepRange::default()
+        );
+        assert_eq!(
+            StepRange::from_predicate(&step().gt(lit(10)).or(step().lt(lit(5)))),
+            StepRange::default()
+        );
+        assert_eq!(StepRange::default().to_expr(), None);
+    }
+
+    #[test]
+    fn scan_pushes_columns_and_steps_down() {
+        let mut log: String = format!("LAMMPS (2 Aug 2023)\nrun 1000\n{MPI_LINE}\n");
+        log.push_str("   Step          Temp          c_count   Atoms\n");
+        for step in (0..=1000).step_by(10) {
+            writeln!(
+                log,
+                "{step:>8} {:>14.8} {:>8} {:>8}",
+                step as f64 / 3.0,
+                step / 10,
+                4000
+            )
+            .unwrap();
+        }
+        writeln!(log, "{LOOP_LINE}").unwrap();
+        let (log_file_name, gz_file_name) = write_logs("scan", &log);
+
+        for log_file_name in [log_file_name, gz_file_name] {
+            let mut lazy_data: LazyFrame =
+                LogLammpsReader::scan(log_file_name.clone(), None).unwrap();
+            let schema: SchemaRef = lazy_data.collect_schema().unwrap();
+            let dtypes: Vec<&DataType> = schema.iter_values().collect();
+            assert_eq!(
+                dtypes,
+                [
+                    &DataType::Int64,
+                    &DataType::Float64,
+                    &DataType::Float64,
+                    &DataType::Int64
+                ]
+            );
+
+            let predicate: Expr = col(STEP_COLUMN)
+                .gt(lit(200.5))
+                .and(col("Temp").lt(lit(100.0)));
+            let scanned: DataFrame = lazy_data
+                .clone()
+                .filter(predicate.clone())
+                .select([col("c_count")])
+                .collect()
+                .unwrap();
+            let parsed: DataFrame = LogLammpsReader::parse(log_file_name.clone(), None)
+                .unwrap()
+                .lazy()
+                .filter(predicate)
+                .select([col("c_count").cast(DataType::Float64)])
+                .collect()
+                .unwrap();
+            assert_eq!(scanned.height(), 9);
+            assert!(scanned.equals_missing(&parsed), "{scanned}\n{parsed}");
+
+            let projected: DataFrame = lazy_data
+                .filter(col(STEP_COLUMN).gt_eq(lit(995)))
+                .select([col(STEP_COLUMN)])
+                .collect()
+                .unwrap();
+            assert_eq!(projected.get_column_names(), [STEP_COLUMN]);
+            let steps = projected.column(STEP_COLUMN).unwrap().i64().unwrap();
+            assert!(steps.into_no_null_iter().eq([1000]));
+
+            assert!(matches!(
+                LogLammpsReader::scan(log_file_name, Some(1)),
+                Err(LammpsError::RunNotFound { run_id: 1, .. })
+            ));
+        }
+
+        // Errors of the parser keep their type through polars
+        let (log_file_name, _) = write_logs("scan_removed", &log);
+        let lazy_data: LazyFrame = LogLammpsReader::scan(log_file_name.clone(), None).unwrap();
+        std::fs::remove_file(&log_file_name).unwrap();
+        let error: PolarsError = lazy_data.collect().unwrap_err();
+        assert!(matches!(
+            LammpsError::from_polars(&log_file_name, error),
+            LammpsError::FileNotFound { .. }
+        ));
+    }
+
+    #[test]
+    fn attributes_diagnostics_to_runs_and_steps() {
+        let log: String = format!(
+            "LAMMPS (2 Aug 2023)
+WARNING: Using 'neigh_modify every 1 delay 0 check yes' setting during minimization (src/min.cpp:219)
+run 200
+{MPI_LINE}
+   Step          Temp
+         0   1.0
+       100   1.1
+WARNING: Lost atoms: original 4000 current 3999 (src/thermo.cpp:488)
+       200   1.2
+{LOOP_LINE}
+WARNING: Bond/angle/dihedral extent > half of periodic box length
+run 100
+{MPI_LINE}
+   Step          Temp
+       200   1.2
+ERROR on proc 0: Out of range atoms - cannot compute PPPM (src/KSPACE/pppm.cpp:1924)
+"
+        );
+        let (log_file_name, _) = write_logs("diagnostics", &log);
+        let diagnostics: Vec<LogDiagnostic> = LogLammpsReader::diagnostics(log_file_name).unwrap();
+        let summary: Vec<(usize, DiagnosticLevel, Option<u32>, Option<u64>)> = diagnostics
+            .iter()
+            .map(|d| (d.line, d.level, d.run_id, d.last_step))
+            .collect();
+        assert_eq!(
+            summary,
+            [
+                (2, DiagnosticLevel::Warning, None, None),
+                (8, DiagnosticLevel::Warning, Some(0), Some(100)),
+                (11, DiagnosticLevel::Warning, None, Some(200)),
+                (16, DiagnosticLevel::Error, Some(1), Some(200)),
+            ]
+        );
+        assert_eq!(
+            diagnostics[1].message,
+            "Lost atoms: original 4000 current 3999"
+        );
+        assert_eq!(
+            diagnostics[1].location.as_deref(),
+            Some("src/thermo.cpp:488")
+        );
+        assert_eq!(diagnostics[2].location, None);
+        assert_eq!(
+            diagnostics[3].message,
+            "Out of range atoms - cannot compute PPPM"
+        );
+        assert_eq!(
+            diagnostics[3].location.as_deref(),
+            Some("src/KSPACE/pppm.cpp:1924")
+        );
+    }
+
+    #[test]
+    fn greps_lines_with_named_captures() {
+        let log: String = format!(
+            "LAMMPS (2 Aug 2023)
+fix 1 all nvt temp 300 300 0.1
+run 100
+{MPI_LINE}
+   Step          Temp
+         0   1.0
+       100   1.1
+{LOOP_LINE}
+unfix 1
+fix 2 all npt temp 300 300 0.1 iso 1 1 1
+run 100
+"
+        );
+        let (log_file_name, _) = write_logs("grep", &log);
+        let patterns: Vec<String> = vec![
+            r"^fix (?P<fix_id>\S+) \S+ (?P<style>\S+)".to_string(),
+            r"^unfix (?P<fix_id>\S+)".to_string(),
+        ];
+        let matches: DataFrame =
+            LogLammpsReader::log_grep(log_file_name.clone(), &patterns, true).unwrap();
+        assert_eq!(
+            matches.get_column_names(),
+            ["line", "run_id", "pattern", "text", "fix_id", "style"]
+        );
+        let column = |name: &str| -> Vec<Option<String>> {
+            matches
+                .column(name)
+                .unwrap()
+                .str()
+                .unwrap()
+                .iter()
+                .map(|value| value.map(String::from))
+                .collect()
+        };
+        assert_eq!(
+            column("fix_id"),
+            [Some("1".into()), Some("1".into()), Some("2".into())]
+        );
+        assert_eq!(
+            column("style"),
+            [Some("nvt".into()), None, Some("npt".into())]
+        );
+        let lines: Vec<u64> = matches
+            .column("line")
+            .unwrap()
+            .u64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(lines, [2, 9, 10]);
+        let run_ids: Vec<u32> = matches
+            .column("run_id")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(run_ids, [0, 1, 1]);
+        let pattern_ids: Vec<u32> = matches
+            .column("pattern")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(pattern_ids, [0, 1, 0]);
+
+        // Prefixes are matched literally and add no capture columns
+        let matches: DataFrame =
+            LogLammpsReader::log_grep(log_file_name, &["run (".to_string()], false).unwrap();
+        assert_eq!(matches.height(), 0);
+        assert_eq!(
+            matches.get_column_names(),
+            ["line", "run_id", "pattern", "text"]
+        );
+    }
+}
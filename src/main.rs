@@ -1,5 +1,4 @@
-mod log_reader;
-use log_reader::LogLammpsReader;
+use log_lammps_reader::LogLammpsReader;
 
 /** Tests the LogLammpsReader by getting arguments from
  CLI. The first is the filename and the second is the optional
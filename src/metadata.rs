@@ -0,0 +1,269 @@
+const VERSION_FLAG: &str = "LAMMPS (";
+const ORTHOGONAL_BOX_FLAG: &str = "orthogonal box =";
+const TRICLINIC_BOX_FLAG: &str = "triclinic box =";
+const PROCESSOR_GRID_FLAG: &str = "MPI processor grid";
+const OPENMP_THREADS_FLAG: &str = "OpenMP thread(s) per MPI task";
+
+/// Simulation box reported by `create_box`, `read_data`, `read_restart` or `change_box`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SimulationBox {
+    pub triclinic: bool,
+    /// `xlo ylo zlo`
+    pub lo: [f64; 3],
+    /// `xhi yhi zhi`
+    pub hi: [f64; 3],
+    /// `xy xz yz` tilt factors of a triclinic box
+    pub tilt: Option<[f64; 3]>,
+}
+
+impl SimulationBox {
+    /** Parses a `Created orthogonal box = (0 0 0) to (10 10 10)` line, or
+    `triclinic box = (0 0 0) to (10 10 10) with tilt (1 0 0)`. */
+    fn from_line(line: &str) -> Option<SimulationBox> {
+        let (triclinic, bounds) = match line.split_once(ORTHOGONAL_BOX_FLAG) {
+            Some((_, bounds)) => (false, bounds),
+            None => (true, line.split_once(TRICLINIC_BOX_FLAG)?.1),
+        };
+        let mut vectors = bounds
+            .split(['(', ')'])
+            .skip(1)
+            .step_by(2)
+            .map(parse_vector);
+        Some(SimulationBox {
+            triclinic,
+            lo: vectors.next()??,
+            hi: vectors.next()??,
+            tilt: if triclinic { vectors.next()? } else { None },
+        })
+    }
+}
+
+/// Settings of the simulation which are echoed or reported in the log file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SimulationSetup {
+    pub units: Option<String>,
+    pub atom_style: Option<String>,
+    pub dimension: Option<u32>,
+    /// Boundary flags of the x, y and z faces, e.g. `p`, `f` or `fs`
+    pub boundary: Option<Vec<String>>,
+    pub timestep: Option<f64>,
+    pub atoms: Option<u64>,
+    pub simulation_box: Option<SimulationBox>,
+    /// Number of MPI processors along x, y and z
+    pub processor_grid: Option<[u32; 3]>,
+    pub mpi_tasks: Option<u32>,
+    pub omp_threads: Option<u32>,
+}
+
+impl SimulationSetup {
+    /** Updates the settings with a line of the log file outside of the thermo output.
+
+    Commands which still contain variables (`${name}`, `$(expression)`) are
+    ignored, LAMMPS echoes them again after the substitution. */
+    pub fn update(&mut self, line: &str) {
+        let line: &str = line.trim();
+        if line.contains(ORTHOGONAL_BOX_FLAG) || line.contains(TRICLINIC_BOX_FLAG) {
+            self.simulation_box = SimulationBox::from_line(line).or(self.simulation_box.take());
+            return;
+        }
+        if line.contains('$') {
+            return;
+        }
+        // Comments after a command are also echoed
+        let tokens: Vec<&str> = line
+            .split('#')
+            .next()
+            .unwrap_or_default()
+            .split_whitespace()
+            .collect();
+        match tokens.as_slice() {
+            ["units", units] => self.units = Some(units.to_string()),
+            ["atom_style", style @ ..] if !style.is_empty() => {
+                self.atom_style = Some(style.join(" "))
+            }
+            ["dimension", dimension] => self.dimension = dimension.parse().ok(),
+            ["boundary", x, y, z] => {
+                self.boundary = Some(vec![x.to_string(), y.to_string(), z.to_string()])
+            }
+            ["timestep", timestep] => self.timestep = timestep.parse().ok(),
+            // Only the MPI/OpenMP layout stays after a clear
+            ["clear"] => {
+                *self = SimulationSetup {
+                    processor_grid: self.processor_grid,
+                    mpi_tasks: self.mpi_tasks,
+                    omp_threads: self.omp_threads,
+                    ..Default::default()
+                }
+            }
+            ["Created", atoms, "atoms"] => {
+                if let Ok(atoms) = atoms.parse::<u64>() {
+                    // create_atoms adds to the atoms which already exist
+                    self.atoms = Some(self.atoms.unwrap_or_default() + atoms);
+                }
+            }
+            // read_data and read_restart print the total number of atoms
+            [atoms, "atoms"] => self.atoms = atoms.parse().ok().or(self.atoms),
+            ["Deleted", .., "new", "total", "=", atoms] => {
+                self.atoms = atoms.parse().ok().or(self.atoms)
+            }
+            [x, "by", y, "by", z, ..] if line.ends_with(PROCESSOR_GRID_FLAG) => {
+                if let (Ok(x), Ok(y), Ok(z)) = (x.parse(), y.parse(), z.parse()) {
+                    self.processor_grid = Some([x, y, z]);
+                    self.mpi_tasks = Some(x * y * z);
+                }
+            }
+            ["using", threads, ..] if line.ends_with(OPENMP_THREADS_FLAG) => {
+                self.omp_threads = threads.parse().ok()
+            }
+            _ => {}
+        }
+    }
+}
+
+/// Settings which applied to a thermo run, taken when the run started
+#[derive(Clone, Debug, PartialEq)]
+pub struct RunMetadata {
+    pub run_id: u32,
+    pub setup: SimulationSetup,
+}
+
+/// Version and settings of the simulation in a log file
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LogMetadata {
+    /// The version in the first line, e.g. `29 Aug 2024`
+    pub version: Option<String>,
+    /// The settings at the end of the log file
+    pub setup: SimulationSetup,
+    pub runs: Vec<RunMetadata>,
+}
+
+impl LogMetadata {
+    /// Updates the metadata with a line of the log file outside of the thermo output
+    pub fn update(&mut self, line: &str) {
+        if self.version.is_none()
+            && let Some(version) = line.trim().strip_prefix(VERSION_FLAG)
+        {
+            self.version = Some(version.trim_end_matches(')').to_string());
+            return;
+        }
+        self.setup.update(line);
+    }
+
+    /// Stores the current settings for a thermo run which is starting
+    pub fn start_run(&mut self, run_id: u32) {
+        self.runs.push(RunMetadata {
+            run_id,
+            setup: self.setup.clone(),
+        });
+    }
+}
+
+/// Parses the numbers of a `0 0 0` vector
+fn parse_vector(values: &str) -> Option<[f64; 3]> {
+    let values: Vec<f64> = values
+        .split_whitespace()
+        .map(|value| value.parse().ok())
+        .collect::<Option<Vec<f64>>>()?;
+    values.try_into().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn read(metadata: &mut LogMetadata, text: &str) {
+        for line in text.lines() {
+            metadata.update(line);
+        }
+    }
+
+    #[test]
+    fn tracks_setup_across_runs() {
+        let mut metadata = LogMetadata::default();
+        read(
+            &mut metadata,
+            "\
+LAMMPS (29 Aug 2024 - Update 1)
+  using 2 OpenMP thread(s) per MPI task
+units metal
+variable T equal 300
+boundary p p ${periodic}
+boundary p p f
+region box block 0 10 0 10 0 10
+create_box 2 box
+Created orthogonal box = (0 0 0) to (36.15 36.15 36.15)
+  2 by 1 by 2 MPI processor grid
+create_atoms 1 box
+Created 4000 atoms
+create_atoms 2 random 100 1234 NULL
+Created 100 atoms
+timestep 0.002 # ps
+",
+        );
+        metadata.start_run(0);
+        read(
+            &mut metadata,
+            "\
+delete_atoms group solvent
+Deleted 250 atoms, new total = 3850
+",
+        );
+        metadata.start_run(1);
+        read(
+            &mut metadata,
+            "\
+clear
+units lj
+atom_style bond
+read_data data.polymer
+Reading data file ...
+  triclinic box = (0 0 0) to (20 20 20) with tilt (1 0 0)
+  1 by 2 by 2 MPI processor grid
+Reading atoms ...
+  1200 atoms
+",
+        );
+        metadata.start_run(2);
+
+        assert_eq!(metadata.version.as_deref(), Some("29 Aug 2024 - Update 1"));
+        let [first, second, third] = &metadata.runs[..] else {
+            panic!("expected three runs, got {:?}", metadata.runs);
+        };
+        assert_eq!(first.setup.units.as_deref(), Some("metal"));
+        assert_eq!(
+            first.setup.boundary,
+            Some(vec!["p".to_string(), "p".to_string(), "f".to_string()])
+        );
+        assert_eq!(first.setup.timestep, Some(0.002));
+        assert_eq!(first.setup.atoms, Some(4100));
+        assert_eq!(first.setup.processor_grid, Some([2, 1, 2]));
+        assert_eq!(first.setup.mpi_tasks, Some(4));
+        assert_eq!(first.setup.omp_threads, Some(2));
+        assert_eq!(
+            first.setup.simulation_box,
+            Some(SimulationBox {
+                triclinic: false,
+                lo: [0.0; 3],
+                hi: [36.15; 3],
+                tilt: None,
+            })
+        );
+
+        assert_eq!(second.run_id, 1);
+        assert_eq!(second.setup.atoms, Some(3850));
+
+        // clear resets everything but the MPI/OpenMP layout
+        assert_eq!(third.setup.units.as_deref(), Some("lj"));
+        assert_eq!(third.setup.atom_style.as_deref(), Some("bond"));
+        assert_eq!(third.setup.boundary, None);
+        assert_eq!(third.setup.timestep, None);
+        assert_eq!(third.setup.atoms, Some(1200));
+        assert_eq!(third.setup.omp_threads, Some(2));
+        assert_eq!(third.setup.processor_grid, Some([1, 2, 2]));
+        assert_eq!(
+            third.setup.simulation_box.as_ref().and_then(|b| b.tilt),
+            Some([1.0, 0.0, 0.0])
+        );
+        assert_eq!(metadata.setup, third.setup);
+    }
+}
@@ -0,0 +1,661 @@
+use polars::prelude::*;
+
+const LOOP_TIME_FLAG: &str = "Loop time of";
+const PERFORMANCE_FLAG: &str = "Performance:";
+const CPU_USE_FLAG: &str = "% CPU use";
+const MPI_TIMING_FLAG: &str = "MPI task timing breakdown:";
+const HISTOGRAM_FLAG: &str = "Histogram:";
+const MINIMIZATION_FLAG: &str = "Minimization stats:";
+
+/// Performance summary which LAMMPS prints after the thermo output of a run
+#[derive(Clone, Debug)]
+pub struct RunPerformance {
+    pub run_id: u32,
+    /// Wall time of the run loop in seconds
+    pub loop_time: f64,
+    pub procs: u32,
+    pub steps: u64,
+    pub atoms: u64,
+    /// `None` for `units lj`, which reports `tau/day` instead
+    pub ns_per_day: Option<f64>,
+    /// `None` for `units lj`, which reports `hours/tau` instead
+    pub hours_per_ns: Option<f64>,
+    pub timesteps_per_s: Option<f64>,
+    pub katom_step_per_s: Option<f64>,
+    /// Percentage of CPU use
+    pub cpu_use: Option<f64>,
+    pub mpi_tasks: Option<u32>,
+    pub omp_threads: Option<u32>,
+    /// The `Section | min time | avg time | max time | %varavg | %total` table
+    pub mpi_timing: DataFrame,
+}
+
+impl RunPerformance {
+    /** Parses the lines after the thermo output of a run.
+
+    The first line must be the `Loop time` line, otherwise `None` is returned. */
+    pub fn from_footer(run_id: u32, footer: &[String]) -> Option<RunPerformance> {
+        let loop_time_tokens: Vec<&str> = footer
+            .first()?
+            .strip_prefix(LOOP_TIME_FLAG)?
+            .split_whitespace()
+            .collect();
+
+        let mut performance = RunPerformance {
+            run_id,
+            loop_time: loop_time_tokens.first()?.parse().ok()?,
+            procs: value_after(&loop_time_tokens, "on")?,
+            steps: value_after(&loop_time_tokens, "for")?,
+            atoms: value_after(&loop_time_tokens, "with")?,
+            ns_per_day: None,
+            hours_per_ns: None,
+            timesteps_per_s: None,
+            katom_step_per_s: None,
+            cpu_use: None,
+            mpi_tasks: None,
+            omp_threads: None,
+            mpi_timing: DataFrame::empty(),
+        };
+
+        for (index, line) in footer.iter().enumerate() {
+            if let Some(rates) = line.strip_prefix(PERFORMANCE_FLAG) {
+                performance.add_rates(rates);
+            } else if line.contains(CPU_USE_FLAG) {
+                let tokens: Vec<&str> = line.split_whitespace().collect();
+                performance.cpu_use = tokens
+                    .first()
+                    .and_then(|value| value.trim_end_matches('%').parse().ok());
+                performance.mpi_tasks = value_before(&tokens, "MPI");
+                performance.omp_threads = value_before(&tokens, "OpenMP");
+            } else if line.starts_with(MPI_TIMING_FLAG) {
+                performance.mpi_timing = mpi_timing_to_df(&footer[index + 1..]).unwrap_or_default();
+            }
+        }
+
+        Some(performance)
+    }
+
+    /// Parses the `6.912 ns/day, 3.472 hours/ns, ...` rates of the `Performance:` line
+    fn add_rates(&mut self, rates: &str) {
+        for rate in rates.split(',') {
+            let mut split = rate.split_whitespace();
+            let (Some(value), Some(unit)) = (split.next(), split.next()) else {
+                continue;
+            };
+            let Ok(value) = value.parse::<f64>() else {
+                continue;
+            };
+            match unit {
+                "ns/day" => self.ns_per_day = Some(value),
+                "hours/ns" => self.hours_per_ns = Some(value),
+                "timesteps/s" => self.timesteps_per_s = Some(value),
+                "katom-step/s" => self.katom_step_per_s = Some(value),
+                "Matom-step/s" => self.katom_step_per_s = Some(value * 1e3),
+                _ => {}
+            }
+        }
+    }
+}
+
+/// Distribution of a per-processor quantity such as `Nlocal` across the MPI ranks
+#[derive(Clone, Debug, Default)]
+pub struct ProcDistribution {
+    pub ave: f64,
+    pub max: f64,
+    pub min: f64,
+    /// Counts of the `Histogram:` line below the quantity
+    pub histogram: Vec<u64>,
+}
+
+impl ProcDistribution {
+    /// Parses a `Nlocal:  519 ave  519 max  519 min` line
+    fn from_line(values: &str) -> Option<ProcDistribution> {
+        let tokens: Vec<&str> = values.split_whitespace().collect();
+        Some(ProcDistribution {
+            ave: value_before(&tokens, "ave")?,
+            max: value_before(&tokens, "max")?,
+            min: value_before(&tokens, "min")?,
+            histogram: Vec::new(),
+        })
+    }
+}
+
+/// Atom and neighbor list statistics which LAMMPS prints after the MPI task timing breakdown
+#[derive(Clone, Debug, Default)]
+pub struct NeighborStats {
+    pub run_id: u32,
+    pub nlocal: Option<ProcDistribution>,
+    pub nghost: Option<ProcDistribution>,
+    pub neighs: Option<ProcDistribution>,
+    pub total_neighbors: Option<u64>,
+    pub ave_neighs_per_atom: Option<f64>,
+    pub ave_special_neighs_per_atom: Option<f64>,
+    pub neighbor_list_builds: Option<u64>,
+    pub dangerous_builds: Option<u64>,
+}
+
+impl NeighborStats {
+    /// Parses the lines after the thermo output of a run, returns `None` if there are no statistics
+    pub fn from_footer(run_id: u32, footer: &[String]) -> Option<NeighborStats> {
+        let mut stats = NeighborStats {
+            run_id,
+            ..Default::default()
+        };
+        let mut found: bool = false;
+
+        for (index, line) in footer.iter().enumerate() {
+            if let Some((name, values)) = line.split_once(':') {
+                let distribution: &mut Option<ProcDistribution> = match name {
+                    "Nlocal" => &mut stats.nlocal,
+                    "Nghost" => &mut stats.nghost,
+                    "Neighs" => &mut stats.neighs,
+                    _ => continue,
+                };
+                *distribution = ProcDistribution::from_line(values).map(|mut distribution| {
+                    distribution.histogram = footer
+                        .get(index + 1)
+                        .and_then(|histogram| histogram.strip_prefix(HISTOGRAM_FLAG))
+                        .map(|counts| {
+                            counts
+                                .split_whitespace()
+                                .filter_map(|count| count.parse().ok())
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    distribution
+                });
+                found |= distribution.is_some();
+            } else if let Some((name, value)) = line.split_once('=') {
+                let value: &str = value.trim();
+                match name.trim() {
+                    "Total # of neighbors" => stats.total_neighbors = value.parse().ok(),
+                    "Ave neighs/atom" => stats.ave_neighs_per_atom = value.parse().ok(),
+                    "Ave special neighs/atom" => {
+                        stats.ave_special_neighs_per_atom = value.parse().ok()
+                    }
+                    "Neighbor list builds" => stats.neighbor_list_builds = value.parse().ok(),
+                    "Dangerous builds" => stats.dangerous_builds = value.parse().ok(),
+                    _ => continue,
+                }
+                found = true;
+            }
+        }
+
+        found.then_some(stats)
+    }
+
+    /// Converts the statistics of several runs into a DataFrame with one row for each run
+    pub fn to_df(stats: &[NeighborStats]) -> PolarsResult<DataFrame> {
+        let mut columns: Vec<Column> = vec![Column::new(
+            "run_id".into(),
+            stats.iter().map(|run| run.run_id).collect::<Vec<u32>>(),
+        )];
+        for (name, distributions) in [
+            (
+                "nlocal",
+                stats.iter().map(|run| &run.nlocal).collect::<Vec<_>>(),
+            ),
+            (
+                "nghost",
+                stats.iter().map(|run| &run.nghost).collect::<Vec<_>>(),
+            ),
+            (
+                "neighs",
+                stats.iter().map(|run| &run.neighs).collect::<Vec<_>>(),
+            ),
+        ] {
+            columns.push(Column::new(
+                format!("{}_ave", name).into(),
+                distributions
+                    .iter()
+                    .map(|d| d.as_ref().map(|d| d.ave))
+                    .collect::<Vec<Option<f64>>>(),
+            ));
+            columns.push(Column::new(
+                format!("{}_max", name).into(),
+                distributions
+                    .iter()
+                    .map(|d| d.as_ref().map(|d| d.max))
+                    .collect::<Vec<Option<f64>>>(),
+            ));
+            columns.push(Column::new(
+                format!("{}_min", name).into(),
+                distributions
+                    .iter()
+                    .map(|d| d.as_ref().map(|d| d.min))
+                    .collect::<Vec<Option<f64>>>(),
+            ));
+            columns.push(Column::new(
+                format!("{}_histogram", name).into(),
+                distributions
+                    .iter()
+                    .map(|d| {
+                        let histogram: Vec<u64> =
+                            d.as_ref().map(|d| d.histogram.clone()).unwrap_or_default();
+                        Series::new("".into(), histogram)
+                    })
+                    .collect::<Vec<Series>>(),
+            ));
+        }
+        columns.extend([
+            Column::new(
+                "total_neighbors".into(),
+                stats
+                    .iter()
+                    .map(|run| run.total_neighbors)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "ave_neighs_per_atom".into(),
+                stats
+                    .iter()
+                    .map(|run| run.ave_neighs_per_atom)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "ave_special_neighs_per_atom".into(),
+                stats
+                    .iter()
+                    .map(|run| run.ave_special_neighs_per_atom)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "neighbor_list_builds".into(),
+                stats
+                    .iter()
+                    .map(|run| run.neighbor_list_builds)
+                    .collect::<Vec<_>>(),
+            ),
+            Column::new(
+                "dangerous_builds".into(),
+                stats
+                    .iter()
+                    .map(|run| run.dangerous_builds)
+                    .collect::<Vec<_>>(),
+            ),
+        ]);
+
+        DataFrame::new(columns)
+    }
+}
+
+/// Summary which LAMMPS prints in the `Minimization stats:` block after a `minimize` run
+#[derive(Clone, Debug)]
+pub struct MinimizationStats {
+    pub run_id: u32,
+    pub stopping_criterion: String,
+    pub energy_initial: f64,
+    pub energy_next_to_last: f64,
+    pub energy_final: f64,
+    pub force_two_norm_initial: f64,
+    pub force_two_norm_final: f64,
+    pub force_max_initial: f64,
+    pub force_max_final: f64,
+    pub line_search_alpha: Option<f64>,
+    pub max_atom_move: Option<f64>,
+    pub iterations: u64,
+    pub force_evaluations: u64,
+}
+
+impl MinimizationStats {
+    /// Parses the lines after the thermo output of a run, returns `None` if it was not a minimization
+    pub fn from_footer(run_id: u32, footer: &[String]) -> Option<MinimizationStats> {
+        let start: usize = footer
+            .iter()
+            .position(|line| line.starts_with(MINIMIZATION_FLAG))?;
+
+        let mut stopping_criterion: Option<String> = None;
+        let mut energy: Vec<f64> = Vec::new();
+        let mut force_two_norm: Vec<f64> = Vec::new();
+        let mut force_max: Vec<f64> = Vec::new();
+        let mut line_search: Vec<f64> = Vec::new();
+        let mut iterations: Vec<u64> = Vec::new();
+
+        let mut lines = footer[start + 1..]
+            .iter()
+            .take_while(|line| !line.trim().is_empty())
+            .peekable();
+        while let Some(line) = lines.next() {
+            let Some((name, values)) = line.split_once('=') else {
+                continue;
+            };
+            let mut values: String = values.trim().to_string();
+            // The energies are printed on the line after the name
+            if values.is_empty()
+                && let Some(next_line) = lines.next_if(|next_line| !next_line.contains('='))
+            {
+                values = next_line.trim().to_string();
+            }
+            match name.trim() {
+                "Stopping criterion" => stopping_criterion = Some(values),
+                "Energy initial, next-to-last, final" => energy = parse_values(&values),
+                "Force two-norm initial, final" => force_two_norm = parse_values(&values),
+                "Force max component initial, final" => force_max = parse_values(&values),
+                "Final line search alpha, max atom move" => line_search = parse_values(&values),
+                "Iterations, force evaluations" => iterations = parse_values(&values),
+                _ => {}
+            }
+        }
+
+        Some(MinimizationStats {
+            run_id,
+            stopping_criterion: stopping_criterion?,
+            energy_initial: *energy.first()?,
+            energy_next_to_last: *energy.get(1)?,
+            energy_final: *energy.get(2)?,
+            force_two_norm_initial: *force_two_norm.first()?,
+            force_two_norm_final: *force_two_norm.get(1)?,
+            force_max_initial: *force_max.first()?,
+            force_max_final: *force_max.get(1)?,
+            line_search_alpha: line_search.first().copied(),
+            max_atom_move: line_search.get(1).copied(),
+            iterations: *iterations.first()?,
+            force_evaluations: *iterations.get(1)?,
+        })
+    }
+
+    /// Converts the statistics of several minimizations into a DataFrame with one row for each run
+    pub fn to_df(stats: &[MinimizationStats]) -> PolarsResult<DataFrame> {
+        df![
+            "run_id" => stats.iter().map(|run| run.run_id).collect::<Vec<u32>>(),
+            "stopping_criterion" => stats
+                .iter()
+                .map(|run| run.stopping_criterion.as_str())
+                .collect::<Vec<&str>>(),
+            "energy_initial" => stats.iter().map(|run| run.energy_initial).collect::<Vec<f64>>(),
+            "energy_next_to_last" => stats
+                .iter()
+                .map(|run| run.energy_next_to_last)
+                .collect::<Vec<f64>>(),
+            "energy_final" => stats.iter().map(|run| run.energy_final).collect::<Vec<f64>>(),
+            "force_two_norm_initial" => stats
+                .iter()
+                .map(|run| run.force_two_norm_initial)
+                .collect::<Vec<f64>>(),
+            "force_two_norm_final" => stats
+                .iter()
+                .map(|run| run.force_two_norm_final)
+                .collect::<Vec<f64>>(),
+            "force_max_initial" => stats
+                .iter()
+                .map(|run| run.force_max_initial)
+                .collect::<Vec<f64>>(),
+            "force_max_final" => stats.iter().map(|run| run.force_max_final).collect::<Vec<f64>>(),
+            "line_search_alpha" => stats
+                .iter()
+                .map(|run| run.line_search_alpha)
+                .collect::<Vec<Option<f64>>>(),
+            "max_atom_move" => stats
+                .iter()
+                .map(|run| run.max_atom_move)
+                .collect::<Vec<Option<f64>>>(),
+            "iterations" => stats.iter().map(|run| run.iterations).collect::<Vec<u64>>(),
+            "force_evaluations" => stats
+                .iter()
+                .map(|run| run.force_evaluations)
+                .collect::<Vec<u64>>(),
+        ]
+    }
+}
+
+/** Converts the MPI task timing table into a DataFrame.
+
+The lines start at the table header and the table ends at the first blank line.
+Empty cells (e.g. in the `Other` row) are null. */
+fn mpi_timing_to_df(lines: &[String]) -> PolarsResult<DataFrame> {
+    let Some(header) = lines.first() else {
+        return Ok(DataFrame::empty());
+    };
+    let header: Vec<&str> = header.split('|').map(str::trim).collect();
+
+    let rows: Vec<Vec<&str>> = lines[1..]
+        .iter()
+        .skip_while(|line| line.starts_with('-'))
+        .take_while(|line| !line.trim().is_empty())
+        .map(|line| line.split('|').map(str::trim).collect::<Vec<&str>>())
+        .filter(|row| row.len() == header.len())
+        .collect();
+
+    let mut columns: Vec<Column> = vec![Column::new(
+        header[0].into(),
+        rows.iter().map(|row| row[0]).collect::<Vec<&str>>(),
+    )];
+    for (index, name) in header.iter().enumerate().skip(1) {
+        let values: Vec<Option<f64>> = rows.iter().map(|row| row[index].parse().ok()).collect();
+        columns.push(Column::new((*name).into(), values));
+    }
+
+    DataFrame::new(columns)
+}
+
+/// Parses every whitespace separated value in a string, skipping the invalid ones
+fn parse_values<T: std::str::FromStr>(values: &str) -> Vec<T> {
+    values
+        .split_whitespace()
+        .filter_map(|value| value.parse().ok())
+        .collect()
+}
+
+/// Returns the value following a keyword, e.g. the number of procs in `on 4 procs`
+fn value_after<T: std::str::FromStr>(tokens: &[&str], key: &str) -> Option<T> {
+    let index: usize = tokens.iter().position(|&token| token == key)?;
+    tokens.get(index + 1)?.parse().ok()
+}
+
+/// Returns the value preceding a keyword, e.g. the number of tasks in `4 MPI tasks`
+fn value_before<T: std::str::FromStr>(tokens: &[&str], key: &str) -> Option<T> {
+    let index: usize = tokens.iter().position(|&token| token == key)?;
+    tokens.get(index.checked_sub(1)?)?.parse().ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn footer(text: &str) -> Vec<String> {
+        text.lines().map(str::to_string).collect()
+    }
+
+    const METAL_FOOTER: &str = "\
+Loop time of 1.25002 on 4 procs for 1000 steps with 4000 atoms
+
+Performance: 6.912 ns/day, 3.472 hours/ns, 799.987 timesteps/s, 3.200 Matom-step/s
+98.7% CPU use with 4 MPI tasks x 2 OpenMP threads
+
+MPI task timing breakdown:
+Section |  min time  |  avg time  |  max time  |%varavg| %total
+---------------------------------------------------------------
+Pair    | 0.91234    | 0.93012    | 0.95121    |   2.1 | 74.41
+Neigh   | 0.10321    | 0.10512    | 0.10732    |   0.4 |  8.41
+Other   |            | 0.01234    |            |       |  0.99
+
+Nlocal:           1000 ave        1012 max         988 min
+";
+
+    #[test]
+    fn parses_metal_performance_and_mpi_timing() {
+        let performance = RunPerformance::from_footer(2, &footer(METAL_FOOTER)).unwrap();
+        assert_eq!(performance.run_id, 2);
+        assert_eq!(performance.loop_time, 1.25002);
+        assert_eq!(
+            (performance.procs, performance.steps, performance.atoms),
+            (4, 1000, 4000)
+        );
+        assert_eq!(performance.ns_per_day, Some(6.912));
+        assert_eq!(performance.hours_per_ns, Some(3.472));
+        assert_eq!(performance.timesteps_per_s, Some(799.987));
+        assert_eq!(performance.katom_step_per_s, Some(3200.0));
+        assert_eq!(performance.cpu_use, Some(98.7));
+        assert_eq!(performance.mpi_tasks, Some(4));
+        assert_eq!(performance.omp_threads, Some(2));
+
+        let timing: &DataFrame = &performance.mpi_timing;
+        assert_eq!(
+            timing.get_column_names(),
+            [
+                "Section", "min time", "avg time", "max time", "%varavg", "%total"
+            ]
+        );
+        assert_eq!(timing.height(), 3);
+        let section: Vec<Option<&str>> = timing
+            .column("Section")
+            .unwrap()
+            .str()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(section, [Some("Pair"), Some("Neigh"), Some("Other")]);
+        let min_time: Vec<Option<f64>> = timing
+            .column("min time")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(min_time, [Some(0.91234), Some(0.10321), None]);
+    }
+
+    #[test]
+    fn parses_lj_performance_without_mpi_timing() {
+        let performance = RunPerformance::from_footer(
+            0,
+            &footer(
+                "\
+Loop time of 0.113 on 1 procs for 100 steps with 256 atoms
+
+Performance: 382299.650 tau/day, 884.953 timesteps/s, 226.548 katom-step/s
+99.2% CPU use with 1 MPI tasks x no OpenMP threads
+",
+            ),
+        )
+        .unwrap();
+        assert_eq!(performance.ns_per_day, None);
+        assert_eq!(performance.hours_per_ns, None);
+        assert_eq!(performance.timesteps_per_s, Some(884.953));
+        assert_eq!(performance.katom_step_per_s, Some(226.548));
+        assert_eq!(performance.cpu_use, Some(99.2));
+        assert_eq!(performance.mpi_tasks, Some(1));
+        assert_eq!(performance.omp_threads, None);
+        assert_eq!(performance.mpi_timing.shape(), (0, 0));
+    }
+
+    #[test]
+    fn requires_loop_time_line() {
+        assert!(RunPerformance::from_footer(0, &footer("Performance: 6.912 ns/day")).is_none());
+        assert!(RunPerformance::from_footer(0, &[]).is_none());
+        assert_eq!(mpi_timing_to_df(&[]).unwrap().shape(), (0, 0));
+    }
+
+    #[test]
+    fn parses_neighbor_stats() {
+        let stats = NeighborStats::from_footer(
+            1,
+            &footer(
+                "\
+Loop time of 1.25002 on 4 procs for 1000 steps with 4000 atoms
+
+Nlocal:           1000 ave        1012 max         988 min
+Histogram: 1 0 0 1 0 0 1 0 0 1
+Nghost:         2625.5 ave        2640 max        2611 min
+Histogram: 2 0 0 0 0 0 0 0 0 2
+Neighs:          37620 ave       38100 max       37150 min
+Histogram: 1 1 0 0 0 0 0 0 1 1
+
+Total # of neighbors = 150480
+Ave neighs/atom = 37.62
+Ave special neighs/atom = 0
+Neighbor list builds = 48
+Dangerous builds = 3
+",
+            ),
+        )
+        .unwrap();
+        assert_eq!(stats.run_id, 1);
+        let nlocal: &ProcDistribution = stats.nlocal.as_ref().unwrap();
+        assert_eq!(
+            (nlocal.ave, nlocal.max, nlocal.min),
+            (1000.0, 1012.0, 988.0)
+        );
+        assert_eq!(nlocal.histogram, [1, 0, 0, 1, 0, 0, 1, 0, 0, 1]);
+        assert_eq!(stats.nghost.as_ref().unwrap().ave, 2625.5);
+        assert_eq!(stats.neighs.as_ref().unwrap().histogram.len(), 10);
+        assert_eq!(stats.total_neighbors, Some(150480));
+        assert_eq!(stats.ave_neighs_per_atom, Some(37.62));
+        assert_eq!(stats.ave_special_neighs_per_atom, Some(0.0));
+        assert_eq!(stats.neighbor_list_builds, Some(48));
+        assert_eq!(stats.dangerous_builds, Some(3));
+
+        let df: DataFrame = NeighborStats::to_df(&[stats]).unwrap();
+        assert_eq!(df.height(), 1);
+        let dangerous: Vec<Option<u64>> = df
+            .column("dangerous_builds")
+            .unwrap()
+            .u64()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(dangerous, [Some(3)]);
+    }
+
+    #[test]
+    fn returns_no_neighbor_stats_without_statistics() {
+        let lines: Vec<String> = footer(METAL_FOOTER)
+            .into_iter()
+            .take_while(|line| !line.starts_with("Nlocal"))
+            .collect();
+        assert!(NeighborStats::from_footer(0, &lines).is_none());
+    }
+
+    #[test]
+    fn parses_minimization_stats() {
+        let stats = MinimizationStats::from_footer(
+            3,
+            &footer(
+                "\
+Loop time of 0.0412 on 1 procs for 52 steps with 500 atoms
+
+99.1% CPU use with 1 MPI tasks x no OpenMP threads
+
+Minimization stats:
+  Stopping criterion = energy tolerance
+  Energy initial, next-to-last, final =
+     -6.76133451492     -6.84262916203     -6.84263059436
+  Search direction =
+  Force two-norm initial, final = 24.3311 0.0421
+  Force max component initial, final = 2.1432 0.00635
+  Final line search alpha, max atom move = 0.0183 0.000116
+  Iterations, force evaluations = 52 101
+
+MPI task timing breakdown:
+",
+            ),
+        )
+        .unwrap();
+        assert_eq!(stats.run_id, 3);
+        assert_eq!(stats.stopping_criterion, "energy tolerance");
+        assert_eq!(
+            (
+                stats.energy_initial,
+                stats.energy_next_to_last,
+                stats.energy_final
+            ),
+            (-6.76133451492, -6.84262916203, -6.84263059436)
+        );
+        assert_eq!(
+            (stats.force_two_norm_initial, stats.force_two_norm_final),
+            (24.3311, 0.0421)
+        );
+        assert_eq!(
+            (stats.force_max_initial, stats.force_max_final),
+            (2.1432, 0.00635)
+        );
+        assert_eq!(stats.line_search_alpha, Some(0.0183));
+        assert_eq!(stats.max_atom_move, Some(0.000116));
+        assert_eq!((stats.iterations, stats.force_evaluations), (52, 101));
+    }
+
+    #[test]
+    fn returns_no_minimization_stats_after_md_run() {
+        assert!(MinimizationStats::from_footer(0, &footer(METAL_FOOTER)).is_none());
+    }
+}
@@ -0,0 +1,279 @@
+use polars::prelude::*;
+
+const STEP_COLUMN: &str = "Step";
+const TIME_COLUMN: &str = "Time";
+
+/// Thermo data of a single run which is added to a timeline
+#[derive(Clone, Debug)]
+pub struct TimelineSegment {
+    pub source_file: String,
+    pub run_id: u32,
+    /// A `reset_timestep` command was used since the previous segment of the same log file
+    pub reset_timestep: bool,
+    pub log_data: DataFrame,
+}
+
+/// First and last `Step` and the last `Time` of a segment after it was stitched
+#[derive(Clone, Copy, Debug)]
+struct SegmentBounds {
+    first_step: i64,
+    last_step: i64,
+    last_time: Option<f64>,
+}
+
+/** Joins thermo runs, in order, into one DataFrame with a continuous `Step` and `Time`.
+
+The steps of a segment are shifted to continue from the previous segment when it
+follows a `reset_timestep`, or when it starts before the first step of the previous
+segment (e.g. a new simulation started from a data file). `Time` is shifted along
+with them. Otherwise the steps are kept as they are, and a segment which starts
+before the previous one ended overlaps it, like a simulation restarted from an
+earlier restart file.
+
+With `drop_overlap`, rows of a segment which are repeated by a later segment are
+dropped, which also drops the last row of a run that is printed again as the
+first row of the next run. The later rows are kept.
+
+Returns a DataFrame with `source_file` and `run_id` columns followed by the thermo
+columns, which are merged when they differ between segments. */
+pub fn stitch_segments(
+    segments: Vec<TimelineSegment>,
+    drop_overlap: bool,
+) -> Result<DataFrame, Box<dyn std::error::Error>> {
+    let mut stitched: Vec<(DataFrame, i64)> = Vec::with_capacity(segments.len());
+    let mut previous: Option<SegmentBounds> = None;
+    let mut step_offset: i64 = 0;
+    let mut time_offset: f64 = 0.0;
+
+    for segment in segments {
+        let mut log_data: DataFrame = segment.log_data;
+        if log_data.height() == 0 {
+            continue;
+        }
+        let Ok(step_column) = log_data.column(STEP_COLUMN) else {
+            return Err(format!(
+                "Thermo run {} of '{}' does not have a '{}' column",
+                segment.run_id, segment.source_file, STEP_COLUMN
+            )
+            .into());
+        };
+        let steps: Vec<Option<i64>> = step_column
+            .cast(&DataType::Int64)?
+            .i64()?
+            .into_iter()
+            .collect();
+        let (Some(first_step), Some(last_step)) = (
+            steps.iter().copied().flatten().next(),
+            steps.iter().copied().flatten().next_back(),
+        ) else {
+            continue;
+        };
+        let times: Option<Vec<Option<f64>>> = match log_data.column(TIME_COLUMN) {
+            Ok(time_column) => Some(
+                time_column
+                    .cast(&DataType::Float64)?
+                    .f64()?
+                    .into_iter()
+                    .collect(),
+            ),
+            Err(_) => None,
+        };
+        let first_time: Option<f64> = times
+            .as_ref()
+            .and_then(|times| times.iter().copied().flatten().next());
+
+        if let Some(previous) = previous
+            && (segment.reset_timestep || first_step + step_offset < previous.first_step)
+        {
+            step_offset = previous.last_step - first_step;
+            if let (Some(last_time), Some(first_time)) = (previous.last_time, first_time) {
+                time_offset = last_time - first_time;
+            }
+        }
+
+        let steps: Vec<Option<i64>> = steps
+            .into_iter()
+            .map(|step| step.map(|step| step + step_offset))
+            .collect();
+        log_data.replace(STEP_COLUMN, Series::new(STEP_COLUMN.into(), steps))?;
+        let mut last_time: Option<f64> = None;
+        if let Some(times) = times {
+            let times: Vec<Option<f64>> = times
+                .into_iter()
+                .map(|time| time.map(|time| time + time_offset))
+                .collect();
+            last_time = times.iter().copied().flatten().next_back();
+            log_data.replace(TIME_COLUMN, Series::new(TIME_COLUMN.into(), times))?;
+        }
+
+        let height: usize = log_data.height();
+        log_data.insert_column(
+            0,
+            Column::new("source_file".into(), vec![segment.source_file; height]),
+        )?;
+        log_data.insert_column(
+            1,
+            Column::new("run_id".into(), vec![segment.run_id; height]),
+        )?;
+
+        let bounds = SegmentBounds {
+            first_step: first_step + step_offset,
+            last_step: last_step + step_offset,
+            last_time,
+        };
+        stitched.push((log_data, bounds.first_step));
+        previous = Some(bounds);
+    }
+
+    if stitched.is_empty() {
+        return Err("No thermo data found in the selected runs".into());
+    }
+
+    if drop_overlap {
+        // Keep the rows before the first step of every later segment
+        let mut next_first_step: Option<i64> = None;
+        for (log_data, first_step) in stitched.iter_mut().rev() {
+            if let Some(next_first_step) = next_first_step {
+                let before_next: BooleanChunked =
+                    log_data.column(STEP_COLUMN)?.i64()?.lt(next_first_step);
+                *log_data = log_data.filter(&before_next)?;
+            }
+            next_first_step =
+                Some(next_first_step.map_or(*first_step, |next| next.min(*first_step)));
+        }
+    }
+
+    let log_data: Vec<LazyFrame> = stitched
+        .into_iter()
+        .map(|(log_data, _)| log_data.lazy())
+        .collect();
+    Ok(concat_lf_diagonal(
+        log_data,
+        UnionArgs {
+            to_supertypes: true,
+            ..Default::default()
+        },
+    )?
+    .collect()?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn segment(run_id: u32, reset_timestep: bool, log_data: DataFrame) -> TimelineSegment {
+        TimelineSegment {
+            source_file: "log.lammps".to_string(),
+            run_id,
+            reset_timestep,
+            log_data,
+        }
+    }
+
+    fn steps(log_data: &DataFrame) -> Vec<i64> {
+        log_data
+            .column(STEP_COLUMN)
+            .unwrap()
+            .i64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    fn run_ids(log_data: &DataFrame) -> Vec<u32> {
+        log_data
+            .column("run_id")
+            .unwrap()
+            .u32()
+            .unwrap()
+            .into_no_null_iter()
+            .collect()
+    }
+
+    #[test]
+    fn shifts_steps_and_time_after_reset_timestep() {
+        let segments = vec![
+            segment(
+                0,
+                false,
+                df!["Step" => [0, 100, 200], "Time" => [0.0, 0.5, 1.0]].unwrap(),
+            ),
+            segment(
+                1,
+                true,
+                df!["Step" => [0, 100], "Time" => [0.0, 0.5]].unwrap(),
+            ),
+        ];
+        let log_data: DataFrame = stitch_segments(segments.clone(), false).unwrap();
+        assert_eq!(steps(&log_data), [0, 100, 200, 200, 300]);
+        let times: Vec<f64> = log_data
+            .column(TIME_COLUMN)
+            .unwrap()
+            .f64()
+            .unwrap()
+            .into_no_null_iter()
+            .collect();
+        assert_eq!(times, [0.0, 0.5, 1.0, 1.0, 1.5]);
+
+        let log_data: DataFrame = stitch_segments(segments, true).unwrap();
+        assert_eq!(steps(&log_data), [0, 100, 200, 300]);
+        assert_eq!(run_ids(&log_data), [0, 0, 1, 1]);
+        assert_eq!(
+            log_data.get_column_names(),
+            ["source_file", "run_id", "Step", "Time"]
+        );
+    }
+
+    #[test]
+    fn drops_rows_repeated_by_an_earlier_restart() {
+        let segments = vec![
+            segment(0, false, df!["Step" => [0, 100, 200, 300, 400]].unwrap()),
+            segment(1, false, df!["Step" => [200, 300]].unwrap()),
+        ];
+        let log_data: DataFrame = stitch_segments(segments.clone(), false).unwrap();
+        assert_eq!(steps(&log_data), [0, 100, 200, 300, 400, 200, 300]);
+
+        let log_data: DataFrame = stitch_segments(segments, true).unwrap();
+        assert_eq!(steps(&log_data), [0, 100, 200, 300]);
+        assert_eq!(run_ids(&log_data), [0, 0, 1, 1]);
+    }
+
+    #[test]
+    fn keeps_continuous_restart_and_merges_columns() {
+        let segments = vec![
+            segment(0, false, df!["Step" => [0, 100, 200], "Temp" => [1.0, 1.1, 1.2]].unwrap()),
+            segment(
+                1,
+                false,
+                df!["Step" => [200, 300, 400], "Temp" => [1.2, 1.3, 1.4], "Press" => [0.5, 0.6, 0.7]]
+                    .unwrap(),
+            ),
+        ];
+        let log_data: DataFrame = stitch_segments(segments.clone(), false).unwrap();
+        assert_eq!(steps(&log_data), [0, 100, 200, 200, 300, 400]);
+
+        let log_data: DataFrame = stitch_segments(segments, true).unwrap();
+        assert_eq!(steps(&log_data), [0, 100, 200, 300, 400]);
+        assert_eq!(run_ids(&log_data), [0, 0, 1, 1, 1]);
+        let press: Vec<Option<f64>> = log_data
+            .column("Press")
+            .unwrap()
+            .f64()
+            .unwrap()
+            .iter()
+            .collect();
+        assert_eq!(press, [None, None, Some(0.5), Some(0.6), Some(0.7)]);
+    }
+
+    #[test]
+    fn fails_without_step_column_or_rows() {
+        assert!(
+            stitch_segments(
+                vec![segment(0, false, df!["Temp" => [1.0]].unwrap())],
+                false
+            )
+            .is_err()
+        );
+        assert!(stitch_segments(vec![segment(0, false, DataFrame::empty())], false).is_err());
+    }
+}